@@ -1,3 +1,4 @@
+use bytes::BytesMut;
 use openssl::ssl::SslStream;
 use rand::rngs::OsRng;
 use rsa::{
@@ -6,8 +7,9 @@ use rsa::{
 };
 use std::io::{Read, Write};
 use std::net::TcpStream;
+use tokio_util::codec::{Decoder, Encoder};
 
-use casita::lap;
+use casita::{codec::LeapCodec, lap};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let ip_addr = std::env::args().nth(1).unwrap_or_else(|| {
@@ -28,7 +30,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let public_key = RsaPublicKey::from(&private_key);
     let public_key_pem = public_key.to_public_key_pem().unwrap();
     let public_key_pem_bytes = public_key_pem.as_bytes();
-    key_file.write(private_key_pem_bytes)?;
+    key_file.write_all(private_key_pem_bytes)?;
 
     let lap_ca = openssl::x509::X509::from_pem(lap::LAP_CA.as_bytes()).unwrap();
     let lap_cert = openssl::x509::X509::from_pem(lap::LAP_CERT.as_bytes()).unwrap();
@@ -115,9 +117,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let cert = signing_result.SigningResult.Certificate;
                     let root_cert = signing_result.SigningResult.RootCertificate;
                     let mut cert_file = std::fs::File::create(cert_name)?;
-                    cert_file.write(cert.as_bytes())?;
+                    cert_file.write_all(cert.as_bytes())?;
                     let mut ca_cert_file = std::fs::File::create(ca_cert_name)?;
-                    ca_cert_file.write(root_cert.as_bytes())?;
+                    ca_cert_file.write_all(root_cert.as_bytes())?;
                 }
                 break;
             }
@@ -129,33 +131,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 struct JsonSocket {
     stream: SslStream<TcpStream>,
+    codec: LeapCodec,
+    read_buffer: BytesMut,
 }
 
 impl JsonSocket {
     pub fn new(stream: SslStream<TcpStream>) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            codec: LeapCodec::new(),
+            read_buffer: BytesMut::new(),
+        }
     }
 
-    pub fn read_message(&mut self) -> Result<serde_json::Value, std::io::Error> {
+    pub fn read_message(&mut self) -> Result<serde_json::Value, casita::Error> {
         let mut intermediate_read_buffer = [0u8; 1024];
-        let mut final_read_buffer = vec![];
-        let mut full_message_read = false;
-        while !full_message_read {
+        loop {
+            if let Some(msg) = self.codec.decode(&mut self.read_buffer)? {
+                return Ok(msg);
+            }
             let bytes_read = self.stream.read(&mut intermediate_read_buffer)?;
-            final_read_buffer.extend_from_slice(&intermediate_read_buffer[..bytes_read]);
-            if final_read_buffer[final_read_buffer.len() - 1] == b'\n'
-                && final_read_buffer[final_read_buffer.len() - 2] == b'\r'
-            {
-                full_message_read = true;
+            if bytes_read == 0 {
+                return self
+                    .codec
+                    .decode_eof(&mut self.read_buffer)?
+                    .ok_or(casita::Error::NotConnected);
             }
+            self.read_buffer
+                .extend_from_slice(&intermediate_read_buffer[..bytes_read]);
         }
-
-        Ok(serde_json::from_slice(&final_read_buffer[..]).unwrap())
     }
 
-    pub fn write_message(&mut self, message: &serde_json::Value) -> Result<(), std::io::Error> {
-        self.stream.write(&message.to_string().as_bytes())?;
-        self.stream.write(&[b'\r', b'\n'])?;
+    pub fn write_message(&mut self, message: &serde_json::Value) -> Result<(), casita::Error> {
+        let mut write_buffer = BytesMut::new();
+        self.codec.encode(message.clone(), &mut write_buffer)?;
+        self.stream.write_all(&write_buffer)?;
         Ok(())
     }
 }
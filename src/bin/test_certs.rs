@@ -16,19 +16,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         PathBuf::from("./caseta.crt"),
         PathBuf::from("./caseta.key"),
     )?;
-    let mut client = casita::Client::new(certs, format!("{}:8081", ip_addr)).await;
+    let client = casita::Client::builder(certs, ip_addr).build()?;
 
-    let ping_msg = leap::Message::new(
-        CommuniqueType::ReadRequest,
-        "/server/1/status/ping".to_owned(),
-    );
-    client.connect().await.unwrap();
-    client.send(ping_msg).await.unwrap();
+    let ping_msg = leap::Message::new(CommuniqueType::ReadRequest, leap::Href::ping());
+    client.connect().await?;
 
-    let pong = client.read_message().await.unwrap();
-    let pong = serde_json::from_value::<leap::Message>(pong).unwrap();
+    let pong = client.request(ping_msg).await?.into_result()?;
     println!("Response from Caseta Hub!");
     println!("{:?}", pong);
 
+    client.disconnect().await?;
+
     Ok(())
 }
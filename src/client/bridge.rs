@@ -0,0 +1,89 @@
+use super::{resources::unexpected_body, Client};
+use crate::{
+    leap::{Body, Device, Href, PingResponse, Project, Server},
+    Error, Result,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BridgeInfo {
+    pub servers: Vec<Server>,
+    pub leap_version: f64,
+    pub project: Project,
+    pub device: Device,
+}
+
+impl BridgeInfo {
+    pub fn serial_number(&self) -> Option<u64> {
+        self.device.serial_number
+    }
+
+    pub fn model_number(&self) -> Option<&str> {
+        self.device.model_number.as_deref()
+    }
+
+    pub fn firmware_version(&self) -> Option<&str> {
+        self.device
+            .firmware_image
+            .as_ref()
+            .map(|image| image.firmware.display_name.as_str())
+    }
+}
+
+impl Client {
+    pub async fn ping(&self) -> Result<PingResponse> {
+        let response = self.read(Href::ping()).await?;
+        match response.body {
+            Some(Body::OnePingResponse(ping)) => Ok(ping),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn servers(&self) -> Result<Vec<Server>> {
+        let response = self.read(Href::collection("server")).await?;
+        match response.body {
+            Some(Body::MultipleServerDefinition(servers)) => Ok(servers),
+            Some(Body::OneServerDefinition(server)) => Ok(vec![server]),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn project(&self) -> Result<Project> {
+        let response = self.read(Href::collection("project")).await?;
+        match response.body {
+            Some(Body::OneProjectDefinition(project)) => Ok(project),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn device(&self, device: &Href) -> Result<Device> {
+        let response = self.read(device).await?;
+        match response.body {
+            Some(Body::OneDeviceDefinition(device)) => Ok(device),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn bridge_info(&self) -> Result<BridgeInfo> {
+        let project = self.project().await?;
+        Ok(BridgeInfo {
+            servers: self.servers().await?,
+            leap_version: self.ping().await?.leap_version,
+            device: self.main_repeater(&project).await?,
+            project,
+        })
+    }
+
+    async fn main_repeater(&self, project: &Project) -> Result<Device> {
+        for href in project.master_devices() {
+            let device = self.device(href).await?;
+            if device.device_type.is_bridge() {
+                return Ok(device);
+            }
+        }
+        self.devices()
+            .await?
+            .into_iter()
+            .find(|device| device.device_type.is_bridge())
+            .ok_or(Error::MainRepeaterNotFound)
+    }
+}
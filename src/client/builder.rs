@@ -0,0 +1,291 @@
+use openssl::ssl::{SslContext, SslContextBuilder, SslMethod};
+use rand::Rng;
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Certs, Client, ClientInner, Connector, StateReporter, TlsConnector};
+use crate::{codec::DEFAULT_MAX_FRAME_SIZE, Error, Result};
+
+pub const DEFAULT_LEAP_PORT: u16 = 8081;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OverflowPolicy {
+    Block,
+    DropNewest,
+    DropOldest,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+}
+
+impl ReconnectPolicy {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.initial_delay.as_secs_f64()
+            * self.multiplier.powi(attempt.min(i32::MAX as u32) as i32);
+        let max_delay = self.max_delay.as_secs_f64();
+        let jitter = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(-self.jitter..=self.jitter)
+        } else {
+            0.0
+        };
+        let delay = backoff.min(max_delay) * (1.0 + jitter);
+        Duration::from_secs_f64(delay.clamp(0.0, max_delay))
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ClientConfig {
+    pub host: String,
+    pub port: u16,
+    pub keep_alive_interval: Duration,
+    pub idle_timeout: Duration,
+    pub connect_timeout: Duration,
+    pub handshake_timeout: Duration,
+    pub request_timeout: Duration,
+    pub read_capacity: usize,
+    pub write_capacity: usize,
+    pub client_tag_prefix: String,
+    pub overflow_policy: OverflowPolicy,
+    pub max_frame_size: usize,
+    pub reconnect_policy: Option<ReconnectPolicy>,
+}
+
+impl ClientConfig {
+    pub fn address(&self) -> String {
+        if self.host.is_empty() {
+            "custom transport".to_owned()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    pub fn keep_alive_client_tag(&self) -> String {
+        format!("{}-keep-alive", self.client_tag_prefix)
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: DEFAULT_LEAP_PORT,
+            keep_alive_interval: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(60),
+            connect_timeout: Duration::from_secs(10),
+            handshake_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(10),
+            read_capacity: 10,
+            write_capacity: 10,
+            client_tag_prefix: "casita".to_owned(),
+            overflow_policy: OverflowPolicy::DropOldest,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            reconnect_policy: None,
+        }
+    }
+}
+
+pub struct ClientBuilder {
+    certs: Option<Certs>,
+    connector: Option<Arc<dyn Connector>>,
+    config: ClientConfig,
+}
+
+impl ClientBuilder {
+    pub fn new(certs: Certs, host: impl Into<String>) -> Self {
+        let (host, port) = split_host_port(host.into());
+        Self {
+            certs: Some(certs),
+            connector: None,
+            config: ClientConfig {
+                host,
+                port,
+                ..ClientConfig::default()
+            },
+        }
+    }
+
+    pub fn with_connector(connector: impl Connector) -> Self {
+        Self {
+            certs: None,
+            connector: Some(Arc::new(connector)),
+            config: ClientConfig::default(),
+        }
+    }
+
+    pub fn connector(mut self, connector: impl Connector) -> Self {
+        self.connector = Some(Arc::new(connector));
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.port = port;
+        self
+    }
+
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.config.keep_alive_interval = interval;
+        self
+    }
+
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.idle_timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    pub fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.config.handshake_timeout = timeout;
+        self
+    }
+
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout = timeout;
+        self
+    }
+
+    pub fn read_capacity(mut self, capacity: usize) -> Self {
+        self.config.read_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn write_capacity(mut self, capacity: usize) -> Self {
+        self.config.write_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn client_tag_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.client_tag_prefix = prefix.into();
+        self
+    }
+
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.config.overflow_policy = policy;
+        self
+    }
+
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.config.max_frame_size = max_frame_size;
+        self
+    }
+
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.config.reconnect_policy = Some(policy);
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let config = Arc::new(self.config);
+        let state = StateReporter::new();
+        let connector = match (self.connector, self.certs) {
+            (Some(connector), _) => connector,
+            (None, Some(_)) if config.host.is_empty() => {
+                return Err(Error::InvalidAddress(config.host.clone()))
+            }
+            (None, Some(certs)) => Arc::new(TlsConnector {
+                ssl_context: ssl_context(&certs)?,
+                config: config.clone(),
+                state: state.clone(),
+            }),
+            (None, None) => return Err(Error::InvalidAddress(config.host.clone())),
+        };
+
+        Ok(Client {
+            inner: Arc::new(ClientInner {
+                config,
+                connector,
+                subscriptions: Default::default(),
+                dispatcher: Default::default(),
+                state,
+                connection: Mutex::new(None),
+                next_client_tag: AtomicU64::new(0),
+            }),
+        })
+    }
+}
+
+fn ssl_context(certs: &Certs) -> Result<SslContext> {
+    let mut context = SslContextBuilder::new(SslMethod::tls())?;
+    context
+        .cert_store_mut()
+        .add_cert(certs.leap_ca_cert.clone())?;
+    context.set_certificate(&certs.leap_cert)?;
+    context.set_private_key(&certs.leap_key)?;
+    Ok(context.build())
+}
+
+fn split_host_port(addr: String) -> (String, u16) {
+    if let Ok(socket_addr) = addr.parse::<SocketAddr>() {
+        return (socket_addr.ip().to_string(), socket_addr.port());
+    }
+    if let Some((host, port)) = addr.rsplit_once(':') {
+        if !host.contains(':') {
+            if let Ok(port) = port.parse() {
+                return (host.to_owned(), port);
+            }
+        }
+    }
+    let host = addr.trim_start_matches('[').trim_end_matches(']');
+    (host.to_owned(), DEFAULT_LEAP_PORT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(addr: &str) -> (String, u16) {
+        split_host_port(addr.to_owned())
+    }
+
+    #[test]
+    fn splits_host_and_port() {
+        assert_eq!(split("192.168.1.5:8082"), ("192.168.1.5".to_owned(), 8082));
+        assert_eq!(
+            split("bridge.local:9000"),
+            ("bridge.local".to_owned(), 9000)
+        );
+    }
+
+    #[test]
+    fn splits_bracketed_ipv6() {
+        assert_eq!(split("[fe80::1]:8082"), ("fe80::1".to_owned(), 8082));
+        assert_eq!(
+            split("[fe80::1]"),
+            ("fe80::1".to_owned(), DEFAULT_LEAP_PORT)
+        );
+    }
+
+    #[test]
+    fn keeps_bare_ipv6_on_default_port() {
+        assert_eq!(split("fe80::1"), ("fe80::1".to_owned(), DEFAULT_LEAP_PORT));
+        assert_eq!(split("::1"), ("::1".to_owned(), DEFAULT_LEAP_PORT));
+    }
+
+    #[test]
+    fn defaults_to_leap_port() {
+        assert_eq!(DEFAULT_LEAP_PORT, 8081);
+        assert_eq!(split("192.168.1.5"), ("192.168.1.5".to_owned(), 8081));
+        assert_eq!(split("bridge.local"), ("bridge.local".to_owned(), 8081));
+    }
+}
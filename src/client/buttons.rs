@@ -0,0 +1,335 @@
+use futures::{stream, Stream, StreamExt};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use super::{
+    dispatch::{deliver, Delivery},
+    Client, OverflowPolicy, Subscription,
+};
+use crate::{
+    leap::{Body, Button, ButtonEventType, ButtonGroup, Device, Href},
+    Result,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gesture {
+    Tap,
+    DoubleTap,
+    LongPress,
+    HoldRepeat { count: u32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct GestureConfig {
+    pub long_press: Duration,
+    pub double_tap_window: Duration,
+    pub hold_repeat_interval: Option<Duration>,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            long_press: Duration::from_millis(500),
+            double_tap_window: Duration::from_millis(300),
+            hold_repeat_interval: Some(Duration::from_millis(250)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PressState {
+    Idle,
+    Pressed {
+        prior_tap: bool,
+        held: bool,
+        repeats: u32,
+        deadline: Option<Instant>,
+    },
+    Released {
+        deadline: Instant,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct GestureDetector {
+    config: GestureConfig,
+    state: PressState,
+}
+
+impl GestureDetector {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            state: PressState::Idle,
+        }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.state {
+            PressState::Idle => None,
+            PressState::Pressed { deadline, .. } => deadline,
+            PressState::Released { deadline } => Some(deadline),
+        }
+    }
+
+    pub fn on_event(&mut self, event: &ButtonEventType, now: Instant) -> Vec<Gesture> {
+        let mut gestures = self.on_timeout(now);
+        match (event, &self.state) {
+            (ButtonEventType::Press, PressState::Idle) => self.press(now, false),
+            (ButtonEventType::Press, PressState::Released { .. }) => self.press(now, true),
+            (
+                ButtonEventType::Release,
+                PressState::Pressed {
+                    prior_tap, held, ..
+                },
+            ) => {
+                if *held {
+                    self.state = PressState::Idle;
+                } else if *prior_tap {
+                    gestures.push(Gesture::DoubleTap);
+                    self.state = PressState::Idle;
+                } else if self.config.double_tap_window.is_zero() {
+                    gestures.push(Gesture::Tap);
+                    self.state = PressState::Idle;
+                } else {
+                    self.state = PressState::Released {
+                        deadline: now + self.config.double_tap_window,
+                    };
+                }
+            }
+            _ => {}
+        }
+        gestures
+    }
+
+    pub fn on_timeout(&mut self, now: Instant) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        while let Some(deadline) = self.deadline().filter(|deadline| *deadline <= now) {
+            match &mut self.state {
+                PressState::Released { .. } => {
+                    gestures.push(Gesture::Tap);
+                    self.state = PressState::Idle;
+                }
+                PressState::Pressed {
+                    prior_tap,
+                    held,
+                    repeats,
+                    deadline: next,
+                } => {
+                    if !*held {
+                        if *prior_tap {
+                            gestures.push(Gesture::Tap);
+                            *prior_tap = false;
+                        }
+                        gestures.push(Gesture::LongPress);
+                        *held = true;
+                    } else {
+                        *repeats += 1;
+                        gestures.push(Gesture::HoldRepeat { count: *repeats });
+                    }
+                    *next = self
+                        .config
+                        .hold_repeat_interval
+                        .filter(|interval| !interval.is_zero())
+                        .map(|interval| deadline + interval);
+                }
+                PressState::Idle => break,
+            }
+        }
+        gestures
+    }
+
+    fn press(&mut self, now: Instant, prior_tap: bool) {
+        self.state = PressState::Pressed {
+            prior_tap,
+            held: false,
+            repeats: 0,
+            deadline: Some(now + self.config.long_press),
+        };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ButtonGesture {
+    pub device: String,
+    pub device_href: Href,
+    pub button: Href,
+    pub button_number: u32,
+    pub gesture: Gesture,
+}
+
+pub struct ButtonGestures {
+    rx: async_channel::Receiver<ButtonGesture>,
+    task: JoinHandle<()>,
+}
+
+impl Stream for ButtonGestures {
+    type Item = ButtonGesture;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+impl Drop for ButtonGestures {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct TrackedButton {
+    template: ButtonGesture,
+    detector: GestureDetector,
+}
+
+impl Client {
+    pub async fn button_groups(&self) -> Result<Vec<ButtonGroup>> {
+        self.read_all(Href::collection("buttongroup"), |body| match body {
+            Body::MultipleButtonGroupDefinition(groups) => Some(groups),
+            Body::OneButtonGroupDefinition(group) => Some(vec![group]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn buttons(&self) -> Result<Vec<Button>> {
+        self.read_all(Href::collection("button"), |body| match body {
+            Body::MultipleButtonDefinition(buttons) => Some(buttons),
+            Body::OneButtonDefinition(button) => Some(vec![button]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn button_gestures(&self, config: GestureConfig) -> Result<ButtonGestures> {
+        let devices = self.devices().await?;
+        let group_devices: HashMap<&Href, &Device> = devices
+            .iter()
+            .flat_map(|device| {
+                device
+                    .button_groups
+                    .iter()
+                    .map(move |group| (group, device))
+            })
+            .collect();
+        let groups = self.button_groups().await?;
+        let button_groups: HashMap<&Href, &Href> = groups
+            .iter()
+            .flat_map(|group| {
+                group
+                    .buttons
+                    .iter()
+                    .map(move |button| (button, &group.href))
+            })
+            .collect();
+
+        let mut buttons = Vec::new();
+        let mut subscriptions = Vec::new();
+        for button in self.buttons().await? {
+            let device = button_groups
+                .get(&button.href)
+                .copied()
+                .or(button.parent.as_ref())
+                .and_then(|group| group_devices.get(group));
+            let device = match device {
+                Some(device) => device,
+                None => {
+                    log::debug!("Button {} has no known device, skipping", button.href);
+                    continue;
+                }
+            };
+            let subscription = self
+                .subscribe(button.href.clone().status().child("event"))
+                .await?;
+            subscriptions.push(subscription);
+            buttons.push(TrackedButton {
+                template: ButtonGesture {
+                    device: device.name.clone(),
+                    device_href: device.href.clone(),
+                    button: button.href,
+                    button_number: button.button_number,
+                    gesture: Gesture::Tap,
+                },
+                detector: GestureDetector::new(config.clone()),
+            });
+        }
+
+        let (tx, rx) = async_channel::bounded(self.inner.config.read_capacity);
+        let task = tokio::spawn(gesture_context(
+            buttons,
+            subscriptions,
+            self.inner.config.overflow_policy,
+            tx,
+            rx.clone(),
+        ));
+        Ok(ButtonGestures { rx, task })
+    }
+}
+
+async fn gesture_context(
+    buttons: Vec<TrackedButton>,
+    subscriptions: Vec<Subscription>,
+    overflow_policy: OverflowPolicy,
+    tx: async_channel::Sender<ButtonGesture>,
+    rx: async_channel::Receiver<ButtonGesture>,
+) {
+    let mut buttons: HashMap<Href, TrackedButton> = buttons
+        .into_iter()
+        .map(|button| (button.template.button.clone(), button))
+        .collect();
+    let mut events = stream::select_all(subscriptions);
+
+    loop {
+        let deadline = buttons
+            .values()
+            .filter_map(|button| button.detector.deadline())
+            .min();
+        let sleep = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                None => futures::future::pending().await,
+            }
+        };
+
+        let mut gestures = Vec::new();
+        tokio::select! {
+            msg = events.next() => {
+                let msg = match msg {
+                    Some(msg) => msg,
+                    None => return,
+                };
+                let status = match msg.body {
+                    Some(Body::OneButtonStatusEvent(status)) => status,
+                    _ => continue,
+                };
+                if let Some(button) = buttons.get_mut(&status.button) {
+                    let now = Instant::now();
+                    for gesture in button.detector.on_event(&status.button_event.event_type, now) {
+                        gestures.push(ButtonGesture { gesture, ..button.template.clone() });
+                    }
+                }
+            },
+            _ = sleep => {
+                let now = Instant::now();
+                for button in buttons.values_mut() {
+                    for gesture in button.detector.on_timeout(now) {
+                        gestures.push(ButtonGesture { gesture, ..button.template.clone() });
+                    }
+                }
+            },
+        }
+
+        for gesture in gestures {
+            match deliver(overflow_policy, &tx, &rx, gesture).await {
+                Delivery::Sent => {}
+                Delivery::Dropped => {
+                    log::warn!("Gesture stream is falling behind, dropping gesture")
+                }
+                Delivery::Closed => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use async_channel::{Receiver, Sender, TrySendError};
+use futures::Stream;
+use serde_json::Value;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use super::OverflowPolicy;
+use crate::{leap::CommuniqueType, Error, Result};
+
+#[derive(Clone, Debug, Default)]
+pub struct MessageFilter {
+    communique_types: Vec<CommuniqueType>,
+    url_prefix: Option<String>,
+    message_body_type: Option<String>,
+}
+
+impl MessageFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn communique_type(mut self, communique_type: CommuniqueType) -> Self {
+        self.communique_types.push(communique_type);
+        self
+    }
+
+    pub fn url_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.url_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn message_body_type(mut self, body_type: impl Into<String>) -> Self {
+        self.message_body_type = Some(body_type.into());
+        self
+    }
+
+    pub fn matches(&self, msg: &Value) -> bool {
+        if !self.communique_types.is_empty() {
+            let communique_type = msg["CommuniqueType"].as_str();
+            let matched = self
+                .communique_types
+                .iter()
+                .any(|expected| Some(expected.as_str()) == communique_type);
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.url_prefix {
+            match msg["Header"]["Url"].as_str() {
+                Some(url) if url.starts_with(prefix.as_str()) => {}
+                _ => return false,
+            }
+        }
+        if let Some(body_type) = &self.message_body_type {
+            if msg["Header"]["MessageBodyType"].as_str() != Some(body_type.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub(crate) enum Delivery {
+    Sent,
+    Dropped,
+    Closed,
+}
+
+pub(crate) async fn deliver<T>(
+    overflow_policy: OverflowPolicy,
+    tx: &Sender<T>,
+    rx: &Receiver<T>,
+    mut msg: T,
+) -> Delivery {
+    match overflow_policy {
+        OverflowPolicy::Block => match tx.send(msg).await {
+            Ok(()) => Delivery::Sent,
+            Err(_) => Delivery::Closed,
+        },
+        OverflowPolicy::DropNewest => match tx.try_send(msg) {
+            Ok(()) => Delivery::Sent,
+            Err(TrySendError::Full(_)) => Delivery::Dropped,
+            Err(TrySendError::Closed(_)) => Delivery::Closed,
+        },
+        OverflowPolicy::DropOldest => {
+            let mut delivery = Delivery::Sent;
+            loop {
+                match tx.try_send(msg) {
+                    Ok(()) => return delivery,
+                    Err(TrySendError::Full(rejected)) => {
+                        let _ = rx.try_recv();
+                        delivery = Delivery::Dropped;
+                        msg = rejected;
+                    }
+                    Err(TrySendError::Closed(_)) => return Delivery::Closed,
+                }
+            }
+        }
+    }
+}
+
+struct ListenerSlot {
+    filter: MessageFilter,
+    tx: Sender<Value>,
+    rx: Receiver<Value>,
+    lagged: Arc<AtomicU64>,
+}
+
+#[derive(Default)]
+pub(crate) struct Dispatcher {
+    listeners: Mutex<Vec<ListenerSlot>>,
+}
+
+impl Dispatcher {
+    pub fn listen(&self, filter: MessageFilter, capacity: usize) -> Listener {
+        let (tx, rx) = async_channel::bounded(capacity);
+        let lagged = Arc::new(AtomicU64::new(0));
+        self.listeners.lock().unwrap().push(ListenerSlot {
+            filter,
+            tx,
+            rx: rx.clone(),
+            lagged: lagged.clone(),
+        });
+        Listener { rx, lagged }
+    }
+
+    pub async fn dispatch(&self, overflow_policy: OverflowPolicy, msg: &Value) {
+        let targets: Vec<_> = {
+            let mut listeners = self.listeners.lock().unwrap();
+            listeners.retain(|slot| !slot.tx.is_closed());
+            listeners
+                .iter()
+                .filter(|slot| slot.filter.matches(msg))
+                .map(|slot| (slot.tx.clone(), slot.rx.clone(), slot.lagged.clone()))
+                .collect()
+        };
+
+        for (tx, rx, lagged) in targets {
+            if let Delivery::Dropped = deliver(overflow_policy, &tx, &rx, msg.clone()).await {
+                log::warn!("Listener is falling behind, dropping message");
+                lagged.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Counts `msg` as missed by every listener it would have reached.
+    pub fn skip(&self, msg: &Value) {
+        let listeners = self.listeners.lock().unwrap();
+        for slot in listeners.iter().filter(|slot| slot.filter.matches(msg)) {
+            slot.lagged.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+pub struct Listener {
+    rx: Receiver<Value>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl Listener {
+    pub async fn recv(&mut self) -> Result<Value> {
+        if let Some(skipped) = self.take_lagged() {
+            return Err(Error::Lagged(skipped));
+        }
+        self.rx.recv().await.map_err(|_| Error::NotConnected)
+    }
+
+    fn take_lagged(&self) -> Option<u64> {
+        match self.lagged.swap(0, Ordering::Relaxed) {
+            0 => None,
+            skipped => Some(skipped),
+        }
+    }
+}
+
+impl Stream for Listener {
+    type Item = Result<Value>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(skipped) = self.take_lagged() {
+            return Poll::Ready(Some(Err(Error::Lagged(skipped))));
+        }
+        Pin::new(&mut self.rx).poll_next(cx).map(|msg| msg.map(Ok))
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.rx.close();
+    }
+}
@@ -0,0 +1,686 @@
+use async_channel::{Receiver, Sender, TrySendError};
+use futures::{SinkExt, StreamExt};
+use openssl::{
+    pkey::{PKey, Private},
+    rsa::Rsa,
+    x509::X509,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{fs::File, io::Read};
+use tokio::io::{ReadHalf, WriteHalf};
+use tokio::sync::{oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+use crate::{
+    codec::LeapCodec,
+    leap::{self, CommuniqueType},
+    Error, Result,
+};
+
+mod bridge;
+mod builder;
+mod buttons;
+mod dispatch;
+mod occupancy;
+mod resources;
+mod scenes;
+mod state;
+mod subscription;
+mod timeclock;
+mod transport;
+
+pub use bridge::BridgeInfo;
+use builder::ClientConfig;
+pub use builder::{ClientBuilder, OverflowPolicy, ReconnectPolicy, DEFAULT_LEAP_PORT};
+pub use buttons::{ButtonGesture, ButtonGestures, Gesture, GestureConfig, GestureDetector};
+use dispatch::{deliver, Delivery, Dispatcher};
+pub use dispatch::{Listener, MessageFilter};
+pub use occupancy::{OccupancyEvent, OccupancyEvents};
+use state::StateReporter;
+pub use state::{ConnectionState, ConnectionStatus};
+pub use subscription::Subscription;
+use subscription::{wait_ready, Registration, SubscriptionRegistry};
+use transport::TlsConnector;
+pub use transport::{BoxTransport, Connector, OnceConnector, Transport};
+
+type WriteStream = FramedWrite<WriteHalf<BoxTransport>, LeapCodec>;
+type ReadStream = FramedRead<ReadHalf<BoxTransport>, LeapCodec>;
+type PendingRequests = Mutex<HashMap<String, oneshot::Sender<Value>>>;
+
+pub struct Certs {
+    leap_ca_cert: X509,
+    leap_cert: X509,
+    leap_key: PKey<Private>,
+}
+
+impl Certs {
+    pub fn new(leap_ca_cert: PathBuf, leap_cert: PathBuf, leap_key: PathBuf) -> Result<Self> {
+        let ca_cert = read_pem(&leap_ca_cert)?;
+        let leap_ca =
+            X509::from_pem(&ca_cert).map_err(|err| Error::certificate(&leap_ca_cert, err))?;
+
+        let cert = read_pem(&leap_cert)?;
+        let leap_cert = X509::from_pem(&cert).map_err(|err| Error::certificate(&leap_cert, err))?;
+
+        let key = read_pem(&leap_key)?;
+        let leap_key = Rsa::private_key_from_pem(&key)
+            .and_then(PKey::from_rsa)
+            .map_err(|err| Error::certificate(&leap_key, err))?;
+
+        Ok(Self {
+            leap_ca_cert: leap_ca,
+            leap_cert,
+            leap_key,
+        })
+    }
+
+    pub fn from_pem(leap_ca_cert: &[u8], leap_cert: &[u8], leap_key: &[u8]) -> Result<Self> {
+        let leap_ca_cert =
+            X509::from_pem(leap_ca_cert).map_err(|err| Error::invalid_pem("leap_ca_cert", err))?;
+        let leap_cert =
+            X509::from_pem(leap_cert).map_err(|err| Error::invalid_pem("leap_cert", err))?;
+        let leap_key = Rsa::private_key_from_pem(leap_key)
+            .and_then(PKey::from_rsa)
+            .map_err(|err| Error::invalid_pem("leap_key", err))?;
+
+        Ok(Self {
+            leap_ca_cert,
+            leap_cert,
+            leap_key,
+        })
+    }
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    let mut file = File::open(path).map_err(|err| Error::certificate(path, err))?;
+    let mut pem = Vec::new();
+    file.read_to_end(&mut pem)
+        .map_err(|err| Error::certificate(path, err))?;
+    Ok(pem)
+}
+
+#[derive(Clone)]
+struct TaskContext {
+    write_tx: Sender<Value>,
+    write_rx: Receiver<Value>,
+    pending: Arc<PendingRequests>,
+    subscriptions: Arc<SubscriptionRegistry>,
+    dispatcher: Arc<Dispatcher>,
+    fan_out_tx: Sender<Value>,
+    fan_out_rx: Receiver<Value>,
+    state: StateReporter,
+    shutdown_rx: Receiver<()>,
+}
+
+struct ConnectionTasks {
+    read: JoinHandle<Result<()>>,
+    write: JoinHandle<Result<()>>,
+    keep_alive: JoinHandle<()>,
+}
+
+impl ConnectionTasks {
+    async fn join(self) -> Result<()> {
+        let read = self.read.await;
+        let write = self.write.await;
+        let keep_alive = self.keep_alive.await;
+        read??;
+        write??;
+        keep_alive?;
+        Ok(())
+    }
+}
+
+struct Connection {
+    shutdown: Sender<()>,
+    driver: JoinHandle<Result<()>>,
+    fan_out: JoinHandle<()>,
+    write_tx: Sender<Value>,
+    pending: Arc<PendingRequests>,
+}
+
+struct ClientInner {
+    config: Arc<ClientConfig>,
+    connector: Arc<dyn Connector>,
+    subscriptions: Arc<SubscriptionRegistry>,
+    dispatcher: Arc<Dispatcher>,
+    state: StateReporter,
+    connection: Mutex<Option<Connection>>,
+    next_client_tag: AtomicU64,
+}
+
+impl Drop for ClientInner {
+    fn drop(&mut self) {
+        if let Ok(connection) = self.connection.get_mut() {
+            if let Some(connection) = connection.take() {
+                connection.shutdown.close();
+            }
+        }
+    }
+}
+
+struct PendingGuard<'a> {
+    pending: &'a PendingRequests,
+    client_tag: String,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.client_tag);
+    }
+}
+
+#[derive(Clone)]
+pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+impl Client {
+    pub async fn new(certs: Certs, addr: String) -> Result<Self> {
+        ClientBuilder::new(certs, addr).build()
+    }
+
+    pub fn builder(certs: Certs, host: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(certs, host)
+    }
+
+    pub async fn connect(&self) -> Result<()> {
+        let inner = &self.inner;
+        if let Some(previous) = self.take_connection() {
+            if let Err(err) = Client::shutdown(previous).await {
+                log::warn!("Previous connection to Lutron Caseta failed: {}", err);
+            }
+        }
+
+        inner.state.set(ConnectionState::Connecting);
+        let stream = match Client::establish(inner.connector.as_ref(), &inner.state).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                inner.state.disconnected(err.clone());
+                return Err(err);
+            }
+        };
+        let (write_tx, write_rx) = async_channel::bounded(inner.config.write_capacity);
+        let pending = Arc::new(PendingRequests::default());
+        let (shutdown_tx, shutdown_rx) = async_channel::bounded(1);
+        let (fan_out_tx, fan_out_rx) = async_channel::bounded(inner.config.read_capacity);
+        let fan_out = tokio::spawn(Client::fan_out_context(
+            inner.config.clone(),
+            inner.dispatcher.clone(),
+            inner.subscriptions.clone(),
+            fan_out_rx.clone(),
+            shutdown_rx.clone(),
+        ));
+        let context = TaskContext {
+            write_tx: write_tx.clone(),
+            write_rx,
+            pending: pending.clone(),
+            subscriptions: inner.subscriptions.clone(),
+            dispatcher: inner.dispatcher.clone(),
+            fan_out_tx,
+            fan_out_rx,
+            state: inner.state.clone(),
+            shutdown_rx,
+        };
+
+        let tasks = Client::spawn_tasks(inner.config.clone(), stream, context.clone());
+        let driver = tokio::spawn(Client::supervisor_context(
+            inner.config.clone(),
+            inner.connector.clone(),
+            context,
+            tasks,
+        ));
+        let previous = inner.connection.lock().unwrap().replace(Connection {
+            shutdown: shutdown_tx,
+            driver,
+            fan_out,
+            write_tx,
+            pending,
+        });
+        if let Some(previous) = previous {
+            previous.shutdown.close();
+        }
+
+        log::info!("Connected to Lutron Caseta at {}", inner.config.address());
+        Ok(())
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        let inner = &self.inner;
+        log::info!(
+            "Disconnecting from Lutron Caseta at {}",
+            inner.config.address()
+        );
+        inner.state.set(ConnectionState::Disconnected {
+            reason: "disconnected by client".to_owned(),
+        });
+        let connection = self.take_connection();
+        inner.subscriptions.clear();
+
+        match connection {
+            Some(connection) => Client::shutdown(connection).await,
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.inner.connection.lock().unwrap().is_some() && self.inner.state.current().is_connected()
+    }
+
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.inner.state.current()
+    }
+
+    pub fn watch_connection_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.inner.state.subscribe()
+    }
+
+    pub fn listen(&self, filter: MessageFilter) -> Listener {
+        self.inner
+            .dispatcher
+            .listen(filter, self.inner.config.read_capacity)
+    }
+
+    pub async fn send_raw(&self, msg: Value) -> Result<()> {
+        let (write_tx, _) = self.channels()?;
+        self.inner.subscriptions.track(&msg);
+        write_tx.send(msg).await?;
+        Ok(())
+    }
+
+    pub async fn send(&self, msg: leap::Message) -> Result<()> {
+        self.send_raw(serde_json::to_value(msg)?).await
+    }
+
+    pub async fn request(&self, mut msg: leap::Message) -> Result<leap::Message> {
+        let (write_tx, pending) = self.channels()?;
+
+        let client_tag = format!(
+            "{}-{}",
+            &self.inner.config.client_tag_prefix,
+            self.inner.next_client_tag.fetch_add(1, Ordering::Relaxed)
+        );
+        msg.header.client_tag = Some(client_tag.clone());
+
+        let msg = serde_json::to_value(msg)?;
+        self.inner.subscriptions.track(&msg);
+
+        let (response_tx, response_rx) = oneshot::channel();
+        pending
+            .lock()
+            .unwrap()
+            .insert(client_tag.clone(), response_tx);
+        let _guard = PendingGuard {
+            pending: &pending,
+            client_tag,
+        };
+        write_tx.send(msg).await?;
+
+        let response = tokio::time::timeout(self.inner.config.request_timeout, response_rx)
+            .await
+            .map_err(|_| Error::Timeout)??;
+        Ok(serde_json::from_value(response)?)
+    }
+
+    pub async fn subscribe(&self, url: impl Into<String>) -> Result<Subscription> {
+        let (write_tx, _) = self.channels()?;
+        let url = url.into();
+        let (subscription, registration) = Subscription::register(
+            &url,
+            self.inner.subscriptions.clone(),
+            write_tx,
+            self.inner.config.read_capacity,
+        );
+        let result = match registration {
+            Registration::Joined(ready) => wait_ready(ready).await,
+            Registration::First(ready) => {
+                let result = self.subscribe_request(&url).await;
+                let _ = ready.send(Some(result.clone()));
+                result
+            }
+        };
+
+        match result {
+            Ok(()) => Ok(subscription),
+            Err(err) => {
+                subscription.release();
+                Err(err)
+            }
+        }
+    }
+
+    async fn subscribe_request(&self, url: &str) -> Result<()> {
+        let request = leap::Message::new(CommuniqueType::SubscribeRequest, url.to_owned());
+        if let Err(err) = self.request(request).await?.into_result() {
+            log::warn!("Failed to subscribe to {}: {}", url, &err);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn channels(&self) -> Result<(Sender<Value>, Arc<PendingRequests>)> {
+        self.inner
+            .connection
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|connection| (connection.write_tx.clone(), connection.pending.clone()))
+            .ok_or(Error::NotConnected)
+    }
+
+    fn take_connection(&self) -> Option<Connection> {
+        self.inner.connection.lock().unwrap().take()
+    }
+
+    async fn shutdown(connection: Connection) -> Result<()> {
+        connection.shutdown.close();
+        let result = connection.driver.await;
+        connection.fan_out.await?;
+        result?
+    }
+
+    async fn establish(connector: &dyn Connector, state: &StateReporter) -> Result<BoxTransport> {
+        let stream = connector.connect().await?;
+        state.set(ConnectionState::Connected);
+        Ok(stream)
+    }
+
+    fn spawn_tasks(
+        config: Arc<ClientConfig>,
+        stream: BoxTransport,
+        context: TaskContext,
+    ) -> ConnectionTasks {
+        let (read, write) = tokio::io::split(stream);
+        let read = FramedRead::new(read, LeapCodec::with_max_frame_size(config.max_frame_size));
+        let write = FramedWrite::new(write, LeapCodec::with_max_frame_size(config.max_frame_size));
+        let (timeout_tx, timeout_rx) = async_channel::bounded(1);
+
+        ConnectionTasks {
+            write: tokio::spawn(Client::write_context(
+                config.clone(),
+                write,
+                context.clone(),
+                timeout_rx.clone(),
+            )),
+            keep_alive: tokio::spawn(Client::keep_alive_context(
+                config.clone(),
+                context.clone(),
+                timeout_rx.clone(),
+            )),
+            read: tokio::spawn(Client::read_context(
+                config, read, context, timeout_tx, timeout_rx,
+            )),
+        }
+    }
+
+    async fn supervisor_context(
+        config: Arc<ClientConfig>,
+        connector: Arc<dyn Connector>,
+        context: TaskContext,
+        mut tasks: ConnectionTasks,
+    ) -> Result<()> {
+        loop {
+            let result = tasks.join().await;
+            if context.shutdown_rx.is_closed() {
+                return result;
+            }
+            let policy = match &config.reconnect_policy {
+                Some(policy) => policy,
+                None => return result,
+            };
+            if let Err(err) = result {
+                log::warn!(
+                    "Connection to Lutron Caseta at {} dropped: {}",
+                    config.address(),
+                    err
+                );
+            }
+
+            let mut attempt = 0;
+            tasks = loop {
+                context.state.set(ConnectionState::Reconnecting {
+                    attempt: attempt + 1,
+                });
+                let delay = policy.delay(attempt);
+                log::info!(
+                    "Reconnecting to Lutron Caseta at {} in {:?}",
+                    config.address(),
+                    delay
+                );
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {},
+                    _ = context.shutdown_rx.recv() => return Ok(()),
+                }
+
+                attempt = attempt.saturating_add(1);
+                let stream = tokio::select! {
+                    stream = Client::establish(connector.as_ref(), &context.state) => stream,
+                    _ = context.shutdown_rx.recv() => return Ok(()),
+                };
+                match stream {
+                    Ok(stream) => {
+                        break Client::spawn_tasks(config.clone(), stream, context.clone())
+                    }
+                    Err(err) => {
+                        log::warn!("Reconnect attempt {} failed: {}", attempt, err);
+                        context.state.record_error(err);
+                    }
+                }
+            };
+            log::info!("Reconnected to Lutron Caseta at {}", config.address());
+
+            for msg in context.subscriptions.requests() {
+                if context.write_tx.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn read_context(
+        config: Arc<ClientConfig>,
+        mut stream: ReadStream,
+        context: TaskContext,
+        timeout_tx: Sender<()>,
+        timeout_rx: Receiver<()>,
+    ) -> Result<()> {
+        let result = tokio::select! {
+            result = Client::dispatch_messages(&config, &mut stream, &context) => result,
+            _ = timeout_rx.recv() => Ok(()),
+            _ = context.shutdown_rx.recv() => Ok(()),
+        };
+        timeout_tx.close();
+        context.pending.lock().unwrap().clear();
+        match result {
+            Err(_) if context.shutdown_rx.is_closed() => Ok(()),
+            Err(err) => {
+                log::error!("Connection to Lutron Caseta lost: {}", err);
+                context.state.disconnected(err.clone());
+                Err(err)
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    async fn dispatch_messages(
+        config: &ClientConfig,
+        stream: &mut ReadStream,
+        context: &TaskContext,
+    ) -> Result<()> {
+        let keep_alive_client_tag = config.keep_alive_client_tag();
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(config.idle_timeout) => {
+                    return Err(Error::Timeout);
+                },
+                msg = stream.next() => {
+                    let msg = match msg {
+                        Some(msg) => msg?,
+                        None => return Err(Error::NotConnected),
+                    };
+                    let client_tag = msg["Header"]["ClientTag"].as_str().map(str::to_owned);
+                    if client_tag.as_ref() == Some(&keep_alive_client_tag) {
+                        continue;
+                    }
+                    if let Some(response_tx) = client_tag.and_then(|tag| context.pending.lock().unwrap().remove(&tag)) {
+                        let _ = response_tx.send(msg);
+                        continue;
+                    }
+
+                    if !Client::queue_fan_out(context, msg) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hands a broadcast to the fan-out task without ever waiting on it, so
+    /// responses keep flowing whatever the overflow policy. When the queue is
+    /// full the oldest queued message is dropped and reported as lag to the
+    /// listeners it would have reached.
+    fn queue_fan_out(context: &TaskContext, mut msg: Value) -> bool {
+        loop {
+            match context.fan_out_tx.try_send(msg) {
+                Ok(()) => return true,
+                Err(TrySendError::Full(rejected)) => {
+                    if let Ok(dropped) = context.fan_out_rx.try_recv() {
+                        log::warn!("Fan-out is falling behind, dropping message");
+                        context.dispatcher.skip(&dropped);
+                    }
+                    msg = rejected;
+                }
+                Err(TrySendError::Closed(_)) => return false,
+            }
+        }
+    }
+
+    async fn fan_out_context(
+        config: Arc<ClientConfig>,
+        dispatcher: Arc<Dispatcher>,
+        subscriptions: Arc<SubscriptionRegistry>,
+        fan_out_rx: Receiver<Value>,
+        shutdown_rx: Receiver<()>,
+    ) {
+        let fan_out = async {
+            while let Ok(msg) = fan_out_rx.recv().await {
+                Client::fan_out(&config, &dispatcher, &subscriptions, msg).await;
+            }
+        };
+        tokio::select! {
+            _ = fan_out => {},
+            _ = shutdown_rx.recv() => {},
+        }
+    }
+
+    async fn fan_out(
+        config: &ClientConfig,
+        dispatcher: &Dispatcher,
+        subscriptions: &SubscriptionRegistry,
+        msg: Value,
+    ) {
+        dispatcher.dispatch(config.overflow_policy, &msg).await;
+
+        let listeners = subscriptions.listeners_for(&msg);
+        if listeners.is_empty() {
+            return;
+        }
+        match serde_json::from_value::<leap::Message>(msg) {
+            Ok(update) => {
+                for (tx, rx) in listeners {
+                    if let Delivery::Dropped =
+                        deliver(config.overflow_policy, &tx, &rx, update.clone()).await
+                    {
+                        log::warn!("Subscription is falling behind, dropping update");
+                    }
+                }
+            }
+            Err(err) => log::warn!("Failed to parse subscription update: {}", err),
+        }
+    }
+
+    async fn write_context(
+        config: Arc<ClientConfig>,
+        mut stream: WriteStream,
+        context: TaskContext,
+        timeout_rx: Receiver<()>,
+    ) -> Result<()> {
+        loop {
+            tokio::select! {
+                _ = timeout_rx.recv() => {
+                    return Ok(());
+                },
+                _ = context.shutdown_rx.recv() => {
+                    return Client::close_stream(&config, &mut stream, &context.write_rx).await;
+                },
+                msg = context.write_rx.recv() => {
+                    let msg = match msg {
+                        Ok(msg) => msg,
+                        Err(_) => return Ok(()),
+                    };
+                    if let Err(err) = stream.send(msg).await {
+                        log::error!("Failed to write to Lutron Caseta: {}", err);
+                        context.state.disconnected(err.clone());
+                        timeout_rx.close();
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn close_stream(
+        config: &ClientConfig,
+        stream: &mut WriteStream,
+        rx: &Receiver<Value>,
+    ) -> Result<()> {
+        let close = async {
+            while let Ok(msg) = rx.try_recv() {
+                stream.feed(msg).await?;
+            }
+            stream.close().await
+        };
+        tokio::time::timeout(config.handshake_timeout, close)
+            .await
+            .map_err(|_| Error::Timeout)?
+    }
+
+    async fn keep_alive_context(
+        config: Arc<ClientConfig>,
+        context: TaskContext,
+        timeout_rx: Receiver<()>,
+    ) {
+        let keep_alive_client_tag = config.keep_alive_client_tag();
+        loop {
+            tokio::select! {
+                _ = timeout_rx.recv() => {
+                    break;
+                },
+                _ = context.shutdown_rx.recv() => {
+                    break;
+                },
+                _ = tokio::time::sleep(config.keep_alive_interval) => {
+                    let msg = json!({
+                        "CommuniqueType": "ReadRequest",
+                        "Header": {
+                            "Url": leap::Href::ping().to_string(),
+                            "ClientTag": &keep_alive_client_tag,
+                        }
+                    });
+                    match tokio::time::timeout(config.keep_alive_interval, context.write_tx.send(msg)).await {
+                        Ok(Ok(())) => {},
+                        Ok(Err(_)) => break,
+                        Err(_) => {
+                            log::warn!("Timed out queueing keep-alive for Lutron Caseta");
+                            context.state.record_error(Error::Timeout);
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
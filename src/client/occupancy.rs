@@ -0,0 +1,117 @@
+use futures::Stream;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::{resources::unexpected_body, Client, Subscription};
+use crate::{
+    leap::{Body, Href, OccupancyGroup, OccupancyGroupStatus, OccupancyStatus},
+    Result,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OccupancyEvent {
+    pub group: Href,
+    pub area: Option<Href>,
+    pub status: OccupancyStatus,
+}
+
+pub struct OccupancyEvents {
+    subscription: Subscription,
+    areas: HashMap<Href, Option<Href>>,
+    last: HashMap<Href, OccupancyStatus>,
+    pending: VecDeque<OccupancyEvent>,
+}
+
+impl OccupancyEvents {
+    pub fn status(&self, group: &Href) -> Option<&OccupancyStatus> {
+        self.last.get(group)
+    }
+
+    fn update(&mut self, statuses: Vec<OccupancyGroupStatus>) {
+        for status in statuses {
+            let group = status.occupancy_group;
+            if self.last.get(&group) == Some(&status.occupancy_status) {
+                continue;
+            }
+            self.last
+                .insert(group.clone(), status.occupancy_status.clone());
+            self.pending.push_back(OccupancyEvent {
+                area: self.areas.get(&group).cloned().flatten(),
+                group,
+                status: status.occupancy_status,
+            });
+        }
+    }
+}
+
+impl Stream for OccupancyEvents {
+    type Item = OccupancyEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            let msg = match Pin::new(&mut self.subscription).poll_next(cx) {
+                Poll::Ready(Some(msg)) => msg,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            match msg.body {
+                Some(Body::MultipleOccupancyGroupStatus(statuses)) => self.update(statuses),
+                Some(Body::OneOccupancyGroupStatus(status)) => self.update(vec![status]),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Client {
+    pub async fn occupancy_groups(&self) -> Result<Vec<OccupancyGroup>> {
+        let response = self.read(Href::collection("occupancygroup")).await?;
+        match response.body {
+            Some(Body::MultipleOccupancyGroupDefinition(groups)) => Ok(groups),
+            Some(Body::OneOccupancyGroupDefinition(group)) => Ok(vec![group]),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn occupancy_statuses(&self) -> Result<Vec<OccupancyGroupStatus>> {
+        let response = self
+            .read(Href::collection("occupancygroup").status())
+            .await?;
+        match response.body {
+            Some(Body::MultipleOccupancyGroupStatus(statuses)) => Ok(statuses),
+            Some(Body::OneOccupancyGroupStatus(status)) => Ok(vec![status]),
+            _ => Err(unexpected_body(&response.header)),
+        }
+    }
+
+    pub async fn occupancy_events(&self) -> Result<OccupancyEvents> {
+        let areas = self
+            .occupancy_groups()
+            .await?
+            .into_iter()
+            .map(|group| {
+                let area = group.area().cloned();
+                (group.href, area)
+            })
+            .collect();
+        let subscription = self
+            .subscribe(Href::collection("occupancygroup").status())
+            .await?;
+        let last = self
+            .occupancy_statuses()
+            .await?
+            .into_iter()
+            .map(|status| (status.occupancy_group, status.occupancy_status))
+            .collect();
+        Ok(OccupancyEvents {
+            subscription,
+            areas,
+            last,
+            pending: VecDeque::new(),
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use super::Client;
+use crate::{
+    leap::{self, Area, AreaTree, Body, CommuniqueType, Device, Href, Paging},
+    Error, Result,
+};
+
+const PAGE_SIZE: u32 = 100;
+const MAX_PAGES: u32 = 100;
+
+impl Client {
+    pub async fn read(&self, url: impl Into<String>) -> Result<leap::Message> {
+        let request = leap::Message::new(CommuniqueType::ReadRequest, url);
+        self.request(request).await?.into_result()
+    }
+
+    pub async fn devices(&self) -> Result<Vec<Device>> {
+        self.read_all(Href::collection("device"), |body| match body {
+            Body::MultipleDeviceDefinition(devices) => Some(devices),
+            Body::OneDeviceDefinition(device) => Some(vec![device]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn areas(&self) -> Result<Vec<Area>> {
+        self.read_all(Href::collection("area"), |body| match body {
+            Body::MultipleAreaDefinition(areas) => Some(areas),
+            Body::OneAreaDefinition(area) => Some(vec![area]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn area_tree(&self) -> Result<AreaTree> {
+        Ok(AreaTree::new(self.areas().await?))
+    }
+
+    pub(crate) async fn read_all<T: PartialEq>(
+        &self,
+        href: Href,
+        page: fn(Body) -> Option<Vec<T>>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+        for _ in 0..MAX_PAGES {
+            let mut request = leap::Message::new(CommuniqueType::ReadRequest, &href);
+            request.header.paging = Some(Paging::new(PAGE_SIZE, offset));
+            let response = self.request(request).await?.into_result()?;
+            let paged = response.header.paging.is_some();
+            let page = match response.body.and_then(page) {
+                Some(page) => page,
+                None => return Err(unexpected_body(&response.header)),
+            };
+            let count = page.len() as u32;
+            let previous = items.len().saturating_sub(PAGE_SIZE as usize);
+            if offset > 0 && items[previous..] == page[..] {
+                log::warn!("{} ignored paging offset {}, stopping", href, offset);
+                return Ok(items);
+            }
+            items.extend(page);
+            if !paged || count < PAGE_SIZE {
+                return Ok(items);
+            }
+            offset += count;
+        }
+        log::warn!("Stopped reading {} after {} pages", href, MAX_PAGES);
+        Ok(items)
+    }
+}
+
+pub(crate) fn unexpected_body(header: &leap::Header) -> Error {
+    Error::UnexpectedBody {
+        url: header.url.clone(),
+        message_body_type: header.message_body_type.clone(),
+    }
+}
@@ -0,0 +1,42 @@
+use super::Client;
+use crate::{
+    leap::{
+        command::{ButtonCommand, PressAndRelease},
+        Body, Href, VirtualButton,
+    },
+    Error, Result,
+};
+
+impl Client {
+    pub async fn virtual_buttons(&self) -> Result<Vec<VirtualButton>> {
+        self.read_all(Href::collection("virtualbutton"), |body| match body {
+            Body::MultipleVirtualButtonDefinition(buttons) => Some(buttons),
+            Body::OneVirtualButtonDefinition(button) => Some(vec![button]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn scenes(&self) -> Result<Vec<VirtualButton>> {
+        let mut buttons = self.virtual_buttons().await?;
+        buttons.retain(|button| button.is_programmed);
+        Ok(buttons)
+    }
+
+    pub async fn activate_scene(&self, scene: &Href) -> Result<()> {
+        let request = PressAndRelease.message(scene)?;
+        self.request(request).await?.into_result()?;
+        Ok(())
+    }
+
+    pub async fn activate_scene_by_name(&self, name: &str) -> Result<VirtualButton> {
+        let scene = self
+            .scenes()
+            .await?
+            .into_iter()
+            .find(|scene| scene.name == name)
+            .ok_or_else(|| Error::SceneNotFound(name.to_owned()))?;
+        self.activate_scene(&scene.href).await?;
+        Ok(scene)
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::watch;
+
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Handshaking,
+    Connected,
+    Reconnecting { attempt: u32 },
+    Disconnected { reason: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    pub since: SystemTime,
+    pub last_connected: Option<SystemTime>,
+    pub last_error: Option<Error>,
+}
+
+impl ConnectionStatus {
+    pub fn is_connected(&self) -> bool {
+        self.state == ConnectionState::Connected
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct StateReporter {
+    tx: Arc<watch::Sender<ConnectionStatus>>,
+    rx: watch::Receiver<ConnectionStatus>,
+}
+
+impl StateReporter {
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(ConnectionStatus {
+            state: ConnectionState::Disconnected {
+                reason: "never connected".to_owned(),
+            },
+            since: SystemTime::now(),
+            last_connected: None,
+            last_error: None,
+        });
+        Self {
+            tx: Arc::new(tx),
+            rx,
+        }
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<ConnectionStatus> {
+        self.rx.clone()
+    }
+
+    pub fn current(&self) -> ConnectionStatus {
+        self.rx.borrow().clone()
+    }
+
+    pub fn set(&self, state: ConnectionState) {
+        let mut status = self.current();
+        if status.state == state {
+            return;
+        }
+        let now = SystemTime::now();
+        if state == ConnectionState::Connected {
+            status.last_connected = Some(now);
+        }
+        status.state = state;
+        status.since = now;
+        let _ = self.tx.send(status);
+    }
+
+    pub fn record_error(&self, err: Error) {
+        let mut status = self.current();
+        status.last_error = Some(err);
+        let _ = self.tx.send(status);
+    }
+
+    pub fn disconnected(&self, err: Error) {
+        let mut status = self.current();
+        if matches!(status.state, ConnectionState::Disconnected { .. }) {
+            return;
+        }
+        status.state = ConnectionState::Disconnected {
+            reason: err.to_string(),
+        };
+        status.since = SystemTime::now();
+        status.last_error = Some(err);
+        let _ = self.tx.send(status);
+    }
+}
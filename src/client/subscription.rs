@@ -0,0 +1,239 @@
+use async_channel::{Receiver, Sender, TrySendError};
+use futures::Stream;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::watch;
+
+use crate::{
+    leap::{self, CommuniqueType},
+    Error, Result,
+};
+
+type Ready = Option<Result<()>>;
+
+pub(crate) enum Registration {
+    First(watch::Sender<Ready>),
+    Joined(watch::Receiver<Ready>),
+}
+
+pub(crate) async fn wait_ready(mut ready: watch::Receiver<Ready>) -> Result<()> {
+    loop {
+        if let Some(result) = ready.borrow().clone() {
+            return result;
+        }
+        if ready.changed().await.is_err() {
+            return ready.borrow().clone().unwrap_or(Err(Error::NotConnected));
+        }
+    }
+}
+
+#[derive(Default)]
+struct SubscriptionEntry {
+    request: Option<Value>,
+    listeners: Vec<(u64, Sender<leap::Message>, Receiver<leap::Message>)>,
+    ready: Option<watch::Receiver<Ready>>,
+}
+
+#[derive(Default)]
+pub(crate) struct SubscriptionRegistry {
+    entries: Mutex<HashMap<String, SubscriptionEntry>>,
+    next_listener_id: AtomicU64,
+}
+
+impl SubscriptionRegistry {
+    pub fn track(&self, msg: &Value) {
+        let url = match msg["Header"]["Url"].as_str() {
+            Some(url) => url.to_owned(),
+            None => return,
+        };
+        let mut entries = self.entries.lock().unwrap();
+        match msg["CommuniqueType"].as_str() {
+            Some("SubscribeRequest") => {
+                entries.entry(url).or_default().request = Some(msg.clone());
+            }
+            Some("UnsubscribeRequest") => {
+                entries.remove(&url);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn requests(&self) -> Vec<Value> {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|entry| entry.request.clone())
+            .collect()
+    }
+
+    pub fn listeners_for(
+        &self,
+        msg: &Value,
+    ) -> Vec<(Sender<leap::Message>, Receiver<leap::Message>)> {
+        if msg["CommuniqueType"].as_str() != Some("UpdateResponse") {
+            return vec![];
+        }
+        let url = msg["Header"]["Url"].as_str();
+        let client_tag = msg["Header"]["ClientTag"].as_str();
+
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(entry_url, entry)| {
+                Some(entry_url.as_str()) == url
+                    || (client_tag.is_some()
+                        && entry
+                            .request
+                            .as_ref()
+                            .map(|request| request["Header"]["ClientTag"].as_str() == client_tag)
+                            .unwrap_or(false))
+            })
+            .flat_map(|(_, entry)| {
+                entry
+                    .listeners
+                    .iter()
+                    .map(|(_, tx, rx)| (tx.clone(), rx.clone()))
+            })
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn add_listener(
+        &self,
+        url: &str,
+        capacity: usize,
+    ) -> (u64, Receiver<leap::Message>, Registration) {
+        let (tx, rx) = async_channel::bounded(capacity);
+        let id = self.next_listener_id.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(url.to_owned()).or_default();
+        let registration = match &entry.ready {
+            Some(ready) if !entry.listeners.is_empty() => Registration::Joined(ready.clone()),
+            _ => {
+                let (ready_tx, ready_rx) = watch::channel(None);
+                entry.ready = Some(ready_rx);
+                Registration::First(ready_tx)
+            }
+        };
+        entry.listeners.push((id, tx, rx.clone()));
+        (id, rx, registration)
+    }
+
+    fn remove_listener(&self, url: &str, id: u64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = match entries.get_mut(url) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        entry
+            .listeners
+            .retain(|(listener_id, _, _)| *listener_id != id);
+        if entry.listeners.is_empty() {
+            entries.remove(url);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub struct Subscription {
+    url: String,
+    id: u64,
+    rx: Receiver<leap::Message>,
+    registry: Arc<SubscriptionRegistry>,
+    write_tx: Sender<Value>,
+    capacity: usize,
+}
+
+impl Subscription {
+    pub(crate) fn register(
+        url: &str,
+        registry: Arc<SubscriptionRegistry>,
+        write_tx: Sender<Value>,
+        capacity: usize,
+    ) -> (Self, Registration) {
+        let (id, rx, registration) = registry.add_listener(url, capacity);
+        let subscription = Self {
+            url: url.to_owned(),
+            id,
+            rx,
+            registry,
+            write_tx,
+            capacity,
+        };
+        (subscription, registration)
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub(crate) fn release(&self) -> bool {
+        self.registry.remove_listener(&self.url, self.id)
+    }
+}
+
+impl Clone for Subscription {
+    fn clone(&self) -> Self {
+        let (subscription, registration) = Subscription::register(
+            &self.url,
+            self.registry.clone(),
+            self.write_tx.clone(),
+            self.capacity,
+        );
+        if let Registration::First(ready) = registration {
+            let _ = ready.send(Some(Ok(())));
+        }
+        subscription
+    }
+}
+
+impl Stream for Subscription {
+    type Item = leap::Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if !self.release() {
+            return;
+        }
+
+        log::debug!("Unsubscribing from {}", &self.url);
+        let msg = leap::Message::new(CommuniqueType::UnsubscribeRequest, self.url.clone());
+        let msg = match serde_json::to_value(msg) {
+            Ok(msg) => msg,
+            Err(_) => return,
+        };
+        // Queue the unsubscribe right away so a subscribe issued after this
+        // drop can't overtake it; only a full write queue defers it to a task.
+        let msg = match self.write_tx.try_send(msg) {
+            Ok(()) | Err(TrySendError::Closed(_)) => return,
+            Err(TrySendError::Full(msg)) => msg,
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let write_tx = self.write_tx.clone();
+                handle.spawn(async move {
+                    let _ = write_tx.send(msg).await;
+                });
+            }
+            Err(_) => log::warn!(
+                "Write queue is full, could not unsubscribe from {}",
+                self.url
+            ),
+        }
+    }
+}
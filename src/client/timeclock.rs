@@ -0,0 +1,35 @@
+use super::Client;
+use crate::{
+    leap::{upcoming_events, Body, Href, Location, Timeclock, TimeclockEvent, UpcomingEvent},
+    Result,
+};
+
+impl Client {
+    pub async fn timeclocks(&self) -> Result<Vec<Timeclock>> {
+        self.read_all(Href::collection("timeclock"), |body| match body {
+            Body::MultipleTimeclockDefinition(timeclocks) => Some(timeclocks),
+            Body::OneTimeclockDefinition(timeclock) => Some(vec![timeclock]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn timeclock_events(&self) -> Result<Vec<TimeclockEvent>> {
+        self.read_all(Href::collection("timeclockevent"), |body| match body {
+            Body::MultipleTimeclockEventDefinition(events) => Some(events),
+            Body::OneTimeclockEventDefinition(event) => Some(vec![event]),
+            _ => None,
+        })
+        .await
+    }
+
+    pub async fn upcoming_timeclock_events(
+        &self,
+        days: u32,
+        location: &Location,
+    ) -> Result<Vec<UpcomingEvent>> {
+        let events = self.timeclock_events().await?;
+        let (today, now) = location.now();
+        Ok(upcoming_events(&events, today, now, days, Some(location)))
+    }
+}
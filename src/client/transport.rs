@@ -0,0 +1,79 @@
+use futures::future::BoxFuture;
+use openssl::ssl::{Ssl, SslContext};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_openssl::SslStream;
+
+use super::{builder::ClientConfig, ConnectionState, StateReporter};
+use crate::{Error, Result};
+
+pub trait Transport: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T> Transport for T where T: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+pub type BoxTransport = Box<dyn Transport>;
+
+pub trait Connector: Send + Sync + 'static {
+    fn connect(&self) -> BoxFuture<'_, Result<BoxTransport>>;
+}
+
+impl<F, Fut, T> Connector for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+    T: Transport,
+{
+    fn connect(&self) -> BoxFuture<'_, Result<BoxTransport>> {
+        let connecting = self();
+        Box::pin(async move { Ok(Box::new(connecting.await?) as BoxTransport) })
+    }
+}
+
+pub struct OnceConnector {
+    transport: Mutex<Option<BoxTransport>>,
+}
+
+impl OnceConnector {
+    pub fn new(transport: impl Transport) -> Self {
+        Self {
+            transport: Mutex::new(Some(Box::new(transport))),
+        }
+    }
+}
+
+impl Connector for OnceConnector {
+    fn connect(&self) -> BoxFuture<'_, Result<BoxTransport>> {
+        let transport = self.transport.lock().unwrap().take();
+        Box::pin(async move { transport.ok_or(Error::NotConnected) })
+    }
+}
+
+pub(crate) struct TlsConnector {
+    pub config: Arc<ClientConfig>,
+    pub ssl_context: SslContext,
+    pub state: StateReporter,
+}
+
+impl Connector for TlsConnector {
+    fn connect(&self) -> BoxFuture<'_, Result<BoxTransport>> {
+        Box::pin(async move {
+            let config = &self.config;
+            let ssl = Ssl::new(&self.ssl_context)?;
+            let stream = tokio::time::timeout(
+                config.connect_timeout,
+                TcpStream::connect((config.host.as_str(), config.port)),
+            )
+            .await
+            .map_err(|_| Error::Timeout)??;
+            self.state.set(ConnectionState::Handshaking);
+            let mut stream = SslStream::new(ssl, stream)?;
+            tokio::time::timeout(config.handshake_timeout, Pin::new(&mut stream).connect())
+                .await
+                .map_err(|_| Error::Timeout)??;
+            Ok(Box::new(stream) as BoxTransport)
+        })
+    }
+}
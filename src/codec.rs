@@ -0,0 +1,113 @@
+use bytes::{Buf, BufMut, BytesMut};
+use serde_json::Value;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Error, Result};
+
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 4 * 1024 * 1024;
+
+const DELIMITER: &[u8] = b"\r\n";
+
+#[derive(Clone, Debug)]
+pub struct LeapCodec {
+    max_frame_size: usize,
+    next_index: usize,
+}
+
+impl LeapCodec {
+    pub fn new() -> Self {
+        Self::with_max_frame_size(DEFAULT_MAX_FRAME_SIZE)
+    }
+
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+        Self {
+            max_frame_size,
+            next_index: 0,
+        }
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+}
+
+impl Default for LeapCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LeapCodec {
+    type Item = Value;
+    type Error = Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Value>> {
+        loop {
+            // A '\r' at the very end of the last scan may be completed by the next read.
+            let search_start = self.next_index.saturating_sub(DELIMITER.len() - 1);
+            let delimiter_idx = buf[search_start..]
+                .windows(DELIMITER.len())
+                .position(|window| window == DELIMITER)
+                .map(|idx| search_start + idx);
+
+            let frame_len = match delimiter_idx {
+                Some(frame_len) => frame_len,
+                None => {
+                    if buf.len() > self.max_frame_size {
+                        return Err(Error::Framing(format!(
+                            "frame exceeds maximum size of {} bytes",
+                            self.max_frame_size
+                        )));
+                    }
+                    self.next_index = buf.len();
+                    return Ok(None);
+                }
+            };
+
+            self.next_index = 0;
+            if frame_len > self.max_frame_size {
+                buf.advance(frame_len + DELIMITER.len());
+                return Err(Error::Framing(format!(
+                    "frame of {} bytes exceeds maximum size of {} bytes",
+                    frame_len, self.max_frame_size
+                )));
+            }
+
+            let frame = buf.split_to(frame_len + DELIMITER.len());
+            let frame = &frame[..frame_len];
+            if frame.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let msg: Value = serde_json::from_slice(frame)?;
+            log::debug!("RX: {}", msg);
+            return Ok(Some(msg));
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Value>> {
+        match self.decode(buf)? {
+            Some(msg) => Ok(Some(msg)),
+            None if buf.iter().all(u8::is_ascii_whitespace) => {
+                buf.clear();
+                Ok(None)
+            }
+            None => Err(Error::Framing(
+                "connection closed in the middle of a message".to_owned(),
+            )),
+        }
+    }
+}
+
+impl Encoder<Value> for LeapCodec {
+    type Error = Error;
+
+    fn encode(&mut self, msg: Value, buf: &mut BytesMut) -> Result<()> {
+        let msg = msg.to_string();
+        log::debug!("TX: {}", &msg);
+        buf.reserve(msg.len() + DELIMITER.len());
+        buf.put_slice(msg.as_bytes());
+        buf.put_slice(DELIMITER);
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::leap::LeapError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Clone, Debug)]
+pub enum Error {
+    Tls(Arc<openssl::ssl::Error>),
+    Io(Arc<io::Error>),
+    Framing(String),
+    Json(Arc<serde_json::Error>),
+    Certificate {
+        path: PathBuf,
+        source: Arc<dyn std::error::Error + Send + Sync>,
+    },
+    InvalidPem {
+        name: &'static str,
+        source: Arc<dyn std::error::Error + Send + Sync>,
+    },
+    InvalidAddress(String),
+    InvalidHref(String),
+    InvalidCommand(String),
+    InvalidTimeclock(String),
+    SceneNotFound(String),
+    MainRepeaterNotFound,
+    UnexpectedBody {
+        url: String,
+        message_body_type: Option<String>,
+    },
+    Leap(LeapError),
+    TaskFailed(String),
+    Lagged(u64),
+    Timeout,
+    NotConnected,
+}
+
+impl Error {
+    pub(crate) fn certificate(
+        path: impl Into<PathBuf>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Error::Certificate {
+            path: path.into(),
+            source: Arc::from(source.into()),
+        }
+    }
+
+    pub(crate) fn invalid_pem(
+        name: &'static str,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Error::InvalidPem {
+            name,
+            source: Arc::from(source.into()),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Tls(err) => write!(f, "TLS error: {}", err),
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Framing(reason) => write!(f, "framing error: {}", reason),
+            Error::Json(err) => write!(f, "JSON error: {}", err),
+            Error::Certificate { path, source } => {
+                write!(f, "failed to load {}: {}", path.display(), source)
+            }
+            Error::InvalidPem { name, source } => write!(f, "invalid PEM for {}: {}", name, source),
+            Error::InvalidAddress(addr) => write!(f, "invalid address: {}", addr),
+            Error::InvalidHref(href) => write!(f, "invalid href: {}", href),
+            Error::InvalidCommand(reason) => write!(f, "invalid command: {}", reason),
+            Error::InvalidTimeclock(reason) => write!(f, "invalid timeclock event: {}", reason),
+            Error::SceneNotFound(scene) => write!(f, "no programmed scene named {}", scene),
+            Error::MainRepeaterNotFound => write!(f, "no main repeater in the project"),
+            Error::UnexpectedBody {
+                url,
+                message_body_type,
+            } => write!(
+                f,
+                "unexpected {} body for {}",
+                message_body_type.as_deref().unwrap_or("untyped"),
+                url
+            ),
+            Error::Leap(err) => write!(f, "LEAP error: {}", err),
+            Error::TaskFailed(reason) => write!(f, "client task failed: {}", reason),
+            Error::Lagged(skipped) => write!(f, "listener lagged, {} messages dropped", skipped),
+            Error::Timeout => write!(f, "timed out"),
+            Error::NotConnected => write!(f, "not connected"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Tls(err) => Some(err.as_ref()),
+            Error::Io(err) => Some(err.as_ref()),
+            Error::Json(err) => Some(err.as_ref()),
+            Error::Certificate { source, .. } => Some(source.as_ref()),
+            Error::InvalidPem { source, .. } => Some(source.as_ref()),
+            Error::Leap(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<openssl::ssl::Error> for Error {
+    fn from(err: openssl::ssl::Error) -> Self {
+        Error::Tls(Arc::new(err))
+    }
+}
+
+impl From<openssl::error::ErrorStack> for Error {
+    fn from(err: openssl::error::ErrorStack) -> Self {
+        Error::Tls(Arc::new(err.into()))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(Arc::new(err))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(Arc::new(err))
+    }
+}
+
+impl From<LeapError> for Error {
+    fn from(err: LeapError) -> Self {
+        Error::Leap(err)
+    }
+}
+
+impl<T> From<async_channel::SendError<T>> for Error {
+    fn from(_: async_channel::SendError<T>) -> Self {
+        Error::NotConnected
+    }
+}
+
+impl From<tokio::task::JoinError> for Error {
+    fn from(err: tokio::task::JoinError) -> Self {
+        Error::TaskFailed(err.to_string())
+    }
+}
+
+impl From<tokio::sync::oneshot::error::RecvError> for Error {
+    fn from(_: tokio::sync::oneshot::error::RecvError) -> Self {
+        Error::NotConnected
+    }
+}
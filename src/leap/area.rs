@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::{Device, Href};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Area {
+    #[serde(flatten)]
+    pub href: Href,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_leaf: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_zones: Vec<Href>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_control_stations: Vec<Href>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_occupancy_groups: Vec<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AreaTree {
+    areas: Vec<Area>,
+    index: HashMap<Href, usize>,
+    children: HashMap<Href, Vec<usize>>,
+    roots: Vec<usize>,
+    zones: HashMap<Href, usize>,
+}
+
+impl AreaTree {
+    pub fn new(areas: Vec<Area>) -> Self {
+        let index: HashMap<Href, usize> = areas
+            .iter()
+            .enumerate()
+            .map(|(i, area)| (area.href.clone(), i))
+            .collect();
+
+        let mut children: HashMap<Href, Vec<usize>> = HashMap::new();
+        let mut roots = Vec::new();
+        let mut zones = HashMap::new();
+        for (i, area) in areas.iter().enumerate() {
+            match &area.parent {
+                Some(parent) if index.contains_key(parent) => {
+                    children.entry(parent.clone()).or_default().push(i)
+                }
+                _ => roots.push(i),
+            }
+            for zone in &area.associated_zones {
+                zones.insert(zone.clone(), i);
+            }
+        }
+
+        Self {
+            areas,
+            index,
+            children,
+            roots,
+            zones,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.areas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.areas.is_empty()
+    }
+
+    pub fn areas(&self) -> &[Area] {
+        &self.areas
+    }
+
+    pub fn get(&self, href: &Href) -> Option<&Area> {
+        self.index.get(href).map(|&i| &self.areas[i])
+    }
+
+    pub fn root(&self) -> Option<&Area> {
+        self.roots.first().map(|&i| &self.areas[i])
+    }
+
+    pub fn roots(&self) -> Vec<&Area> {
+        self.roots.iter().map(|&i| &self.areas[i]).collect()
+    }
+
+    pub fn parent(&self, href: &Href) -> Option<&Area> {
+        self.get(self.get(href)?.parent.as_ref()?)
+    }
+
+    pub fn children(&self, href: &Href) -> Vec<&Area> {
+        self.children
+            .get(href)
+            .map(|children| children.iter().map(|&i| &self.areas[i]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn path(&self, href: &Href) -> Vec<&Area> {
+        let mut path = Vec::new();
+        let mut next = self.get(href);
+        while let Some(area) = next {
+            if path.len() == self.areas.len() {
+                log::warn!("Area {} has a cyclic parent chain", href);
+                break;
+            }
+            path.push(area);
+            next = area.parent.as_ref().and_then(|parent| self.get(parent));
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn zones(&self, href: &Href) -> &[Href] {
+        self.get(href)
+            .map(|area| area.associated_zones.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn area_for_zone(&self, zone: &Href) -> Option<&Area> {
+        self.zones.get(zone).map(|&i| &self.areas[i])
+    }
+
+    pub fn zone_path(&self, zone: &Href) -> Vec<&Area> {
+        match self.area_for_zone(zone) {
+            Some(area) => self.path(&area.href),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn device_path(&self, device: &Device) -> Vec<&Area> {
+        match &device.associated_area {
+            Some(area) => self.path(area),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn walk(&self) -> Vec<(usize, &Area)> {
+        let mut walked = Vec::with_capacity(self.areas.len());
+        let mut seen = vec![false; self.areas.len()];
+        let mut stack: Vec<(usize, usize)> = self.roots.iter().rev().map(|&i| (0, i)).collect();
+        while let Some((depth, i)) = stack.pop() {
+            if std::mem::replace(&mut seen[i], true) {
+                continue;
+            }
+            walked.push((depth, &self.areas[i]));
+            if let Some(children) = self.children.get(&self.areas[i].href) {
+                stack.extend(children.iter().rev().map(|&child| (depth + 1, child)));
+            }
+        }
+        walked
+    }
+}
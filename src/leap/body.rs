@@ -0,0 +1,240 @@
+use serde::{de::DeserializeOwned, ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::{
+    Area, Button, ButtonGroup, ButtonStatus, Device, OccupancyGroup, OccupancyGroupStatus,
+    PingResponse, Project, Server, Timeclock, TimeclockEvent, VirtualButton, ZoneDefinition,
+    ZoneStatus,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Body {
+    OneZoneStatus(ZoneStatus),
+    MultipleZoneStatus(Vec<ZoneStatus>),
+    OneZoneDefinition(ZoneDefinition),
+    MultipleZoneDefinition(Vec<ZoneDefinition>),
+    OneDeviceDefinition(Device),
+    MultipleDeviceDefinition(Vec<Device>),
+    OneAreaDefinition(Area),
+    MultipleAreaDefinition(Vec<Area>),
+    OneButtonGroupDefinition(ButtonGroup),
+    MultipleButtonGroupDefinition(Vec<ButtonGroup>),
+    OneButtonDefinition(Button),
+    MultipleButtonDefinition(Vec<Button>),
+    OneButtonStatusEvent(ButtonStatus),
+    OneVirtualButtonDefinition(VirtualButton),
+    MultipleVirtualButtonDefinition(Vec<VirtualButton>),
+    OneOccupancyGroupDefinition(OccupancyGroup),
+    MultipleOccupancyGroupDefinition(Vec<OccupancyGroup>),
+    OneOccupancyGroupStatus(OccupancyGroupStatus),
+    MultipleOccupancyGroupStatus(Vec<OccupancyGroupStatus>),
+    OneServerDefinition(Server),
+    MultipleServerDefinition(Vec<Server>),
+    OneProjectDefinition(Project),
+    OneTimeclockDefinition(Timeclock),
+    MultipleTimeclockDefinition(Vec<Timeclock>),
+    OneTimeclockEventDefinition(TimeclockEvent),
+    MultipleTimeclockEventDefinition(Vec<TimeclockEvent>),
+    OnePingResponse(PingResponse),
+    ExceptionDetail(ExceptionDetail),
+    Raw(Value),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExceptionDetail {
+    pub message: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Body {
+    pub fn from_value(message_body_type: Option<&str>, value: Value) -> Self {
+        let typed = match message_body_type {
+            Some("OneZoneStatus") => field(&value, "ZoneStatus").map(Body::OneZoneStatus),
+            Some("MultipleZoneStatus") => {
+                field(&value, "ZoneStatuses").map(Body::MultipleZoneStatus)
+            }
+            Some("OneZoneDefinition") => field(&value, "Zone").map(Body::OneZoneDefinition),
+            Some("MultipleZoneDefinition") => {
+                field(&value, "Zones").map(Body::MultipleZoneDefinition)
+            }
+            Some("OneDeviceDefinition") => field(&value, "Device").map(Body::OneDeviceDefinition),
+            Some("MultipleDeviceDefinition") => {
+                field(&value, "Devices").map(Body::MultipleDeviceDefinition)
+            }
+            Some("OneAreaDefinition") => field(&value, "Area").map(Body::OneAreaDefinition),
+            Some("MultipleAreaDefinition") => {
+                field(&value, "Areas").map(Body::MultipleAreaDefinition)
+            }
+            Some("OneButtonGroupDefinition") => {
+                field(&value, "ButtonGroup").map(Body::OneButtonGroupDefinition)
+            }
+            Some("MultipleButtonGroupDefinition") => {
+                field(&value, "ButtonGroups").map(Body::MultipleButtonGroupDefinition)
+            }
+            Some("OneButtonDefinition") => field(&value, "Button").map(Body::OneButtonDefinition),
+            Some("MultipleButtonDefinition") => {
+                field(&value, "Buttons").map(Body::MultipleButtonDefinition)
+            }
+            Some("OneButtonStatusEvent") => {
+                field(&value, "ButtonStatus").map(Body::OneButtonStatusEvent)
+            }
+            Some("OneVirtualButtonDefinition") => {
+                field(&value, "VirtualButton").map(Body::OneVirtualButtonDefinition)
+            }
+            Some("MultipleVirtualButtonDefinition") => {
+                field(&value, "VirtualButtons").map(Body::MultipleVirtualButtonDefinition)
+            }
+            Some("OneOccupancyGroupDefinition") => {
+                field(&value, "OccupancyGroup").map(Body::OneOccupancyGroupDefinition)
+            }
+            Some("MultipleOccupancyGroupDefinition") => {
+                field(&value, "OccupancyGroups").map(Body::MultipleOccupancyGroupDefinition)
+            }
+            Some("OneOccupancyGroupStatus") => {
+                field(&value, "OccupancyGroupStatus").map(Body::OneOccupancyGroupStatus)
+            }
+            Some("MultipleOccupancyGroupStatus") => {
+                field(&value, "OccupancyGroupStatuses").map(Body::MultipleOccupancyGroupStatus)
+            }
+            Some("OneServerDefinition") => field(&value, "Server").map(Body::OneServerDefinition),
+            Some("MultipleServerDefinition") => {
+                field(&value, "Servers").map(Body::MultipleServerDefinition)
+            }
+            Some("OneProjectDefinition") => {
+                field(&value, "Project").map(Body::OneProjectDefinition)
+            }
+            Some("OneTimeclockDefinition") => {
+                field(&value, "Timeclock").map(Body::OneTimeclockDefinition)
+            }
+            Some("MultipleTimeclockDefinition") => {
+                field(&value, "Timeclocks").map(Body::MultipleTimeclockDefinition)
+            }
+            Some("OneTimeclockEventDefinition") => {
+                field(&value, "TimeclockEvent").map(Body::OneTimeclockEventDefinition)
+            }
+            Some("MultipleTimeclockEventDefinition") => {
+                field(&value, "TimeclockEvents").map(Body::MultipleTimeclockEventDefinition)
+            }
+            Some("OnePingResponse") => field(&value, "PingResponse").map(Body::OnePingResponse),
+            Some("ExceptionDetail") => serde_json::from_value(value.clone())
+                .ok()
+                .map(Body::ExceptionDetail),
+            _ => {
+                if let Some(body_type) = message_body_type {
+                    log::debug!("Keeping {} body untyped", body_type);
+                }
+                return Body::Raw(value);
+            }
+        };
+        match typed {
+            Some(body) => body,
+            None => {
+                log::warn!(
+                    "Failed to parse {} body, keeping it untyped",
+                    message_body_type.unwrap_or_default()
+                );
+                Body::Raw(value)
+            }
+        }
+    }
+
+    pub fn message_body_type(&self) -> Option<&'static str> {
+        match self {
+            Body::OneZoneStatus(_) => Some("OneZoneStatus"),
+            Body::MultipleZoneStatus(_) => Some("MultipleZoneStatus"),
+            Body::OneZoneDefinition(_) => Some("OneZoneDefinition"),
+            Body::MultipleZoneDefinition(_) => Some("MultipleZoneDefinition"),
+            Body::OneDeviceDefinition(_) => Some("OneDeviceDefinition"),
+            Body::MultipleDeviceDefinition(_) => Some("MultipleDeviceDefinition"),
+            Body::OneAreaDefinition(_) => Some("OneAreaDefinition"),
+            Body::MultipleAreaDefinition(_) => Some("MultipleAreaDefinition"),
+            Body::OneButtonGroupDefinition(_) => Some("OneButtonGroupDefinition"),
+            Body::MultipleButtonGroupDefinition(_) => Some("MultipleButtonGroupDefinition"),
+            Body::OneButtonDefinition(_) => Some("OneButtonDefinition"),
+            Body::MultipleButtonDefinition(_) => Some("MultipleButtonDefinition"),
+            Body::OneButtonStatusEvent(_) => Some("OneButtonStatusEvent"),
+            Body::OneVirtualButtonDefinition(_) => Some("OneVirtualButtonDefinition"),
+            Body::MultipleVirtualButtonDefinition(_) => Some("MultipleVirtualButtonDefinition"),
+            Body::OneOccupancyGroupDefinition(_) => Some("OneOccupancyGroupDefinition"),
+            Body::MultipleOccupancyGroupDefinition(_) => Some("MultipleOccupancyGroupDefinition"),
+            Body::OneOccupancyGroupStatus(_) => Some("OneOccupancyGroupStatus"),
+            Body::MultipleOccupancyGroupStatus(_) => Some("MultipleOccupancyGroupStatus"),
+            Body::OneServerDefinition(_) => Some("OneServerDefinition"),
+            Body::MultipleServerDefinition(_) => Some("MultipleServerDefinition"),
+            Body::OneProjectDefinition(_) => Some("OneProjectDefinition"),
+            Body::OneTimeclockDefinition(_) => Some("OneTimeclockDefinition"),
+            Body::MultipleTimeclockDefinition(_) => Some("MultipleTimeclockDefinition"),
+            Body::OneTimeclockEventDefinition(_) => Some("OneTimeclockEventDefinition"),
+            Body::MultipleTimeclockEventDefinition(_) => Some("MultipleTimeclockEventDefinition"),
+            Body::OnePingResponse(_) => Some("OnePingResponse"),
+            Body::ExceptionDetail(_) => Some("ExceptionDetail"),
+            Body::Raw(_) => None,
+        }
+    }
+}
+
+fn field<T: DeserializeOwned>(value: &Value, key: &str) -> Option<T> {
+    serde_json::from_value(value.get(key)?.clone()).ok()
+}
+
+impl Serialize for Body {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Body::OneZoneStatus(status) => entry(serializer, "ZoneStatus", status),
+            Body::MultipleZoneStatus(statuses) => entry(serializer, "ZoneStatuses", statuses),
+            Body::OneZoneDefinition(zone) => entry(serializer, "Zone", zone),
+            Body::MultipleZoneDefinition(zones) => entry(serializer, "Zones", zones),
+            Body::OneDeviceDefinition(device) => entry(serializer, "Device", device),
+            Body::MultipleDeviceDefinition(devices) => entry(serializer, "Devices", devices),
+            Body::OneAreaDefinition(area) => entry(serializer, "Area", area),
+            Body::MultipleAreaDefinition(areas) => entry(serializer, "Areas", areas),
+            Body::OneButtonGroupDefinition(group) => entry(serializer, "ButtonGroup", group),
+            Body::MultipleButtonGroupDefinition(groups) => {
+                entry(serializer, "ButtonGroups", groups)
+            }
+            Body::OneButtonDefinition(button) => entry(serializer, "Button", button),
+            Body::MultipleButtonDefinition(buttons) => entry(serializer, "Buttons", buttons),
+            Body::OneButtonStatusEvent(status) => entry(serializer, "ButtonStatus", status),
+            Body::OneVirtualButtonDefinition(button) => entry(serializer, "VirtualButton", button),
+            Body::MultipleVirtualButtonDefinition(buttons) => {
+                entry(serializer, "VirtualButtons", buttons)
+            }
+            Body::OneOccupancyGroupDefinition(group) => entry(serializer, "OccupancyGroup", group),
+            Body::MultipleOccupancyGroupDefinition(groups) => {
+                entry(serializer, "OccupancyGroups", groups)
+            }
+            Body::OneOccupancyGroupStatus(status) => {
+                entry(serializer, "OccupancyGroupStatus", status)
+            }
+            Body::MultipleOccupancyGroupStatus(statuses) => {
+                entry(serializer, "OccupancyGroupStatuses", statuses)
+            }
+            Body::OneServerDefinition(server) => entry(serializer, "Server", server),
+            Body::MultipleServerDefinition(servers) => entry(serializer, "Servers", servers),
+            Body::OneProjectDefinition(project) => entry(serializer, "Project", project),
+            Body::OneTimeclockDefinition(timeclock) => entry(serializer, "Timeclock", timeclock),
+            Body::MultipleTimeclockDefinition(timeclocks) => {
+                entry(serializer, "Timeclocks", timeclocks)
+            }
+            Body::OneTimeclockEventDefinition(event) => entry(serializer, "TimeclockEvent", event),
+            Body::MultipleTimeclockEventDefinition(events) => {
+                entry(serializer, "TimeclockEvents", events)
+            }
+            Body::OnePingResponse(ping) => entry(serializer, "PingResponse", ping),
+            Body::ExceptionDetail(detail) => detail.serialize(serializer),
+            Body::Raw(value) => value.serialize(serializer),
+        }
+    }
+}
+
+fn entry<S: Serializer, T: Serialize>(
+    serializer: S,
+    key: &str,
+    value: &T,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(key, value)?;
+    map.end()
+}
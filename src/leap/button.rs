@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+string_enum! {
+    pub enum ButtonEventType {
+        Press => "Press",
+        Release => "Release",
+        LongHold => "LongHold",
+        MultiTap => "MultiTap",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ButtonStatus {
+    pub button: Href,
+    pub button_event: ButtonEvent,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ButtonEvent {
+    pub event_type: ButtonEventType,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ButtonGroup {
+    #[serde(flatten)]
+    pub href: Href,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buttons: Vec<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Button {
+    #[serde(flatten)]
+    pub href: Href,
+    pub button_number: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VirtualButton {
+    #[serde(flatten)]
+    pub href: Href,
+    #[serde(default)]
+    pub name: String,
+    pub button_number: u32,
+    #[serde(default)]
+    pub is_programmed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programming_model: Option<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
@@ -0,0 +1,418 @@
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+use super::{Body, CommuniqueType, FanSpeed, Href, Message, SwitchedLevel};
+use crate::{Error, Result};
+
+const MAX_DURATION_SECS: u64 = 99 * 3600 + 59 * 60 + 59;
+
+pub trait ZoneCommand {
+    fn command(&self) -> Result<Value>;
+
+    fn message(&self, zone: u32) -> Result<Message> {
+        let command = self.command()?;
+        Ok(Message::new(
+            CommuniqueType::CreateRequest,
+            Href::zone(zone).commandprocessor(),
+        )
+        .with_body(Body::Raw(json!({ "Command": command }))))
+    }
+}
+
+pub trait ButtonCommand {
+    fn command(&self) -> Result<Value>;
+
+    fn message(&self, button: &Href) -> Result<Message> {
+        if !matches!(button.kind(), "button" | "virtualbutton") || button.id().is_none() {
+            return Err(Error::InvalidCommand(format!(
+                "{} is not a button or virtual button",
+                button
+            )));
+        }
+        let command = self.command()?;
+        Ok(Message::new(
+            CommuniqueType::CreateRequest,
+            button.root().commandprocessor(),
+        )
+        .with_body(Body::Raw(json!({ "Command": command }))))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToLevel {
+    pub level: u8,
+}
+
+impl GoToLevel {
+    pub fn new(level: u8) -> Self {
+        Self { level }
+    }
+}
+
+impl ZoneCommand for GoToLevel {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({
+            "CommandType": "GoToLevel",
+            "Parameter": [{ "Type": "Level", "Value": percent("Level", self.level)? }],
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToDimmedLevel {
+    pub level: u8,
+    pub fade_time: Option<Duration>,
+    pub delay_time: Option<Duration>,
+}
+
+impl GoToDimmedLevel {
+    pub fn new(level: u8) -> Self {
+        Self {
+            level,
+            fade_time: None,
+            delay_time: None,
+        }
+    }
+
+    pub fn fade_time(mut self, fade_time: Duration) -> Self {
+        self.fade_time = Some(fade_time);
+        self
+    }
+
+    pub fn delay_time(mut self, delay_time: Duration) -> Self {
+        self.delay_time = Some(delay_time);
+        self
+    }
+}
+
+impl ZoneCommand for GoToDimmedLevel {
+    fn command(&self) -> Result<Value> {
+        let mut parameters = Map::new();
+        parameters.insert("Level".to_owned(), json!(percent("Level", self.level)?));
+        insert_duration(&mut parameters, "FadeTime", self.fade_time)?;
+        insert_duration(&mut parameters, "DelayTime", self.delay_time)?;
+        Ok(json!({
+            "CommandType": "GoToDimmedLevel",
+            "DimmedLevelParameters": parameters,
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToSwitchedLevel {
+    pub level: SwitchedLevel,
+    pub delay_time: Option<Duration>,
+}
+
+impl GoToSwitchedLevel {
+    pub fn new(level: SwitchedLevel) -> Self {
+        Self {
+            level,
+            delay_time: None,
+        }
+    }
+
+    pub fn delay_time(mut self, delay_time: Duration) -> Self {
+        self.delay_time = Some(delay_time);
+        self
+    }
+}
+
+impl ZoneCommand for GoToSwitchedLevel {
+    fn command(&self) -> Result<Value> {
+        if let SwitchedLevel::Unknown(level) = &self.level {
+            return Err(Error::InvalidCommand(format!(
+                "unknown switched level {}",
+                level
+            )));
+        }
+        let mut parameters = Map::new();
+        parameters.insert("SwitchedLevel".to_owned(), json!(self.level));
+        insert_duration(&mut parameters, "DelayTime", self.delay_time)?;
+        Ok(json!({
+            "CommandType": "GoToSwitchedLevel",
+            "SwitchedLevelParameters": parameters,
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToFanSpeed {
+    pub speed: FanSpeed,
+}
+
+impl GoToFanSpeed {
+    pub fn new(speed: FanSpeed) -> Self {
+        Self { speed }
+    }
+}
+
+impl ZoneCommand for GoToFanSpeed {
+    fn command(&self) -> Result<Value> {
+        if let FanSpeed::Unknown(speed) = &self.speed {
+            return Err(Error::InvalidCommand(format!(
+                "unknown fan speed {}",
+                speed
+            )));
+        }
+        Ok(json!({
+            "CommandType": "GoToFanSpeed",
+            "FanSpeedParameters": { "FanSpeed": self.speed },
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Raise;
+
+impl ZoneCommand for Raise {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({ "CommandType": "Raise" }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lower;
+
+impl ZoneCommand for Lower {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({ "CommandType": "Lower" }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stop;
+
+impl ZoneCommand for Stop {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({ "CommandType": "Stop" }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToShadeLevel {
+    pub level: u8,
+}
+
+impl GoToShadeLevel {
+    pub fn new(level: u8) -> Self {
+        Self { level }
+    }
+}
+
+impl ZoneCommand for GoToShadeLevel {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({
+            "CommandType": "GoToShadeLevel",
+            "ShadeLevelParameters": { "Level": percent("Level", self.level)? },
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToTilt {
+    pub tilt: u8,
+}
+
+impl GoToTilt {
+    pub fn new(tilt: u8) -> Self {
+        Self { tilt }
+    }
+}
+
+impl ZoneCommand for GoToTilt {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({
+            "CommandType": "GoToTilt",
+            "TiltParameters": { "Tilt": percent("Tilt", self.tilt)? },
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToWhiteTuningLevel {
+    pub kelvin: u16,
+    pub level: Option<u8>,
+    pub fade_time: Option<Duration>,
+}
+
+impl GoToWhiteTuningLevel {
+    pub fn new(kelvin: u16) -> Self {
+        Self {
+            kelvin,
+            level: None,
+            fade_time: None,
+        }
+    }
+
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    pub fn fade_time(mut self, fade_time: Duration) -> Self {
+        self.fade_time = Some(fade_time);
+        self
+    }
+}
+
+impl ZoneCommand for GoToWhiteTuningLevel {
+    fn command(&self) -> Result<Value> {
+        if !(1400..=10000).contains(&self.kelvin) {
+            return Err(Error::InvalidCommand(format!(
+                "Kelvin must be between 1400 and 10000, got {}",
+                self.kelvin
+            )));
+        }
+        let mut parameters = Map::new();
+        parameters.insert(
+            "WhiteTuningLevel".to_owned(),
+            json!({ "Kelvin": self.kelvin }),
+        );
+        if let Some(level) = self.level {
+            parameters.insert("Level".to_owned(), json!(percent("Level", level)?));
+        }
+        insert_duration(&mut parameters, "FadeTime", self.fade_time)?;
+        Ok(json!({
+            "CommandType": "GoToWhiteTuningLevel",
+            "WhiteTuningLevelParameters": parameters,
+        }))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoToSpectrumTuningLevel {
+    pub hue: u16,
+    pub saturation: u8,
+    pub level: Option<u8>,
+    pub vibrancy: Option<u8>,
+    pub fade_time: Option<Duration>,
+}
+
+impl GoToSpectrumTuningLevel {
+    pub fn new(hue: u16, saturation: u8) -> Self {
+        Self {
+            hue,
+            saturation,
+            level: None,
+            vibrancy: None,
+            fade_time: None,
+        }
+    }
+
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    pub fn vibrancy(mut self, vibrancy: u8) -> Self {
+        self.vibrancy = Some(vibrancy);
+        self
+    }
+
+    pub fn fade_time(mut self, fade_time: Duration) -> Self {
+        self.fade_time = Some(fade_time);
+        self
+    }
+}
+
+impl ZoneCommand for GoToSpectrumTuningLevel {
+    fn command(&self) -> Result<Value> {
+        if self.hue > 360 {
+            return Err(Error::InvalidCommand(format!(
+                "Hue must be between 0 and 360, got {}",
+                self.hue
+            )));
+        }
+        let mut parameters = Map::new();
+        parameters.insert(
+            "ColorTuningStatus".to_owned(),
+            json!({
+                "HSVTuningLevel": {
+                    "Hue": self.hue,
+                    "Saturation": percent("Saturation", self.saturation)?,
+                }
+            }),
+        );
+        if let Some(level) = self.level {
+            parameters.insert("Level".to_owned(), json!(percent("Level", level)?));
+        }
+        if let Some(vibrancy) = self.vibrancy {
+            parameters.insert("Vibrancy".to_owned(), json!(percent("Vibrancy", vibrancy)?));
+        }
+        insert_duration(&mut parameters, "FadeTime", self.fade_time)?;
+        Ok(json!({
+            "CommandType": "GoToSpectrumTuningLevel",
+            "SpectrumTuningLevelParameters": parameters,
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PressAndRelease;
+
+impl ButtonCommand for PressAndRelease {
+    fn command(&self) -> Result<Value> {
+        Ok(json!({ "CommandType": "PressAndRelease" }))
+    }
+}
+
+pub fn format_duration(duration: Duration) -> Result<String> {
+    if duration.subsec_nanos() != 0 {
+        return Err(Error::InvalidCommand(format!(
+            "duration {:?} must be a whole number of seconds",
+            duration
+        )));
+    }
+    let secs = duration.as_secs();
+    if secs > MAX_DURATION_SECS {
+        return Err(Error::InvalidCommand(format!(
+            "duration {:?} is longer than 99:59:59",
+            duration
+        )));
+    }
+    Ok(format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    ))
+}
+
+pub fn parse_duration(duration: &str) -> Result<Duration> {
+    let invalid = || Error::InvalidCommand(format!("invalid duration {:?}", duration));
+    let parts: Vec<&str> = duration.split(':').collect();
+    if parts.len() != 3 || parts.iter().any(|part| part.len() != 2) {
+        return Err(invalid());
+    }
+    let mut values = [0u64; 3];
+    for (value, part) in values.iter_mut().zip(&parts) {
+        *value = part.parse().map_err(|_| invalid())?;
+    }
+    let [hours, minutes, seconds] = values;
+    if minutes > 59 || seconds > 59 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(hours * 3600 + minutes * 60 + seconds))
+}
+
+fn percent(name: &str, value: u8) -> Result<u8> {
+    if value > 100 {
+        return Err(Error::InvalidCommand(format!(
+            "{} must be between 0 and 100, got {}",
+            name, value
+        )));
+    }
+    Ok(value)
+}
+
+fn insert_duration(
+    parameters: &mut Map<String, Value>,
+    name: &str,
+    duration: Option<Duration>,
+) -> Result<()> {
+    if let Some(duration) = duration {
+        parameters.insert(name.to_owned(), json!(format_duration(duration)?));
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+string_enum! {
+    pub enum DeviceType {
+        SmartBridge => "SmartBridge",
+        SmartBridgePro => "SmartBridgePro",
+        MainRepeater => "RA2SelectMainRepeater",
+        Pico1Button => "Pico1Button",
+        Pico2Button => "Pico2Button",
+        Pico2ButtonRaiseLower => "Pico2ButtonRaiseLower",
+        Pico3Button => "Pico3Button",
+        Pico3ButtonRaiseLower => "Pico3ButtonRaiseLower",
+        Pico4Button => "Pico4Button",
+        Pico4ButtonScene => "Pico4ButtonScene",
+        Pico4ButtonZone => "Pico4ButtonZone",
+        Pico4Button2Group => "Pico4Button2Group",
+        FourGroupRemote => "FourGroupRemote",
+        WallDimmer => "WallDimmer",
+        PlugInDimmer => "PlugInDimmer",
+        WallSwitch => "WallSwitch",
+        PlugInSwitch => "PlugInSwitch",
+        FanSpeedController => "CasetaFanSpeedController",
+        SerenaRollerShade => "SerenaRollerShade",
+        SerenaHoneycombShade => "SerenaHoneycombShade",
+        SerenaTiltOnlyWoodBlind => "SerenaTiltOnlyWoodBlind",
+        TriathlonRollerShade => "TriathlonRollerShade",
+        TriathlonHoneycombShade => "TriathlonHoneycombShade",
+        QsWirelessShade => "QsWirelessShade",
+        OccupancySensor => "RPSOccupancySensor",
+    }
+}
+
+impl DeviceType {
+    pub fn is_bridge(&self) -> bool {
+        matches!(
+            self,
+            DeviceType::SmartBridge | DeviceType::SmartBridgePro | DeviceType::MainRepeater
+        )
+    }
+
+    pub fn is_pico(&self) -> bool {
+        self.as_str().starts_with("Pico") || *self == DeviceType::FourGroupRemote
+    }
+
+    pub fn is_shade(&self) -> bool {
+        self.as_str().contains("Shade") || self.as_str().contains("Blind")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Device {
+    #[serde(flatten)]
+    pub href: Href,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fully_qualified_name: Vec<String>,
+    pub device_type: DeviceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_zones: Vec<Href>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub button_groups: Vec<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub associated_area: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firmware_image: Option<FirmwareImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_firmware_package: Option<FirmwarePackage>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct FirmwareImage {
+    pub firmware: Firmware,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed: Option<Timestamp>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct FirmwarePackage {
+    pub package: Firmware,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Firmware {
+    pub display_name: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Timestamp {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    #[serde(default)]
+    pub hour: u8,
+    #[serde(default)]
+    pub minute: u8,
+    #[serde(default)]
+    pub second: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
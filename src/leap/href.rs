@@ -0,0 +1,190 @@
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HrefSegment {
+    pub name: String,
+    pub id: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Href {
+    segments: Vec<HrefSegment>,
+}
+
+impl Href {
+    pub fn resource(kind: &str, id: u32) -> Self {
+        Self {
+            segments: vec![HrefSegment {
+                name: kind.to_owned(),
+                id: Some(id),
+            }],
+        }
+    }
+
+    pub fn collection(kind: &str) -> Self {
+        Self {
+            segments: vec![HrefSegment {
+                name: kind.to_owned(),
+                id: None,
+            }],
+        }
+    }
+
+    pub fn area(id: u32) -> Self {
+        Self::resource("area", id)
+    }
+
+    pub fn zone(id: u32) -> Self {
+        Self::resource("zone", id)
+    }
+
+    pub fn device(id: u32) -> Self {
+        Self::resource("device", id)
+    }
+
+    pub fn button(id: u32) -> Self {
+        Self::resource("button", id)
+    }
+
+    pub fn button_group(id: u32) -> Self {
+        Self::resource("buttongroup", id)
+    }
+
+    pub fn occupancy_group(id: u32) -> Self {
+        Self::resource("occupancygroup", id)
+    }
+
+    pub fn virtual_button(id: u32) -> Self {
+        Self::resource("virtualbutton", id)
+    }
+
+    pub fn server(id: u32) -> Self {
+        Self::resource("server", id)
+    }
+
+    pub fn ping() -> Self {
+        Self::server(1).status().child("ping")
+    }
+
+    pub fn child(mut self, name: &str) -> Self {
+        self.segments.push(HrefSegment {
+            name: name.to_owned(),
+            id: None,
+        });
+        self
+    }
+
+    pub fn child_resource(mut self, name: &str, id: u32) -> Self {
+        self.segments.push(HrefSegment {
+            name: name.to_owned(),
+            id: Some(id),
+        });
+        self
+    }
+
+    pub fn status(self) -> Self {
+        self.child("status")
+    }
+
+    pub fn commandprocessor(self) -> Self {
+        self.child("commandprocessor")
+    }
+
+    pub fn segments(&self) -> &[HrefSegment] {
+        &self.segments
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.segments[0].name
+    }
+
+    pub fn id(&self) -> Option<u32> {
+        self.segments[0].id
+    }
+
+    pub fn ids(&self) -> Vec<u32> {
+        self.segments
+            .iter()
+            .filter_map(|segment| segment.id)
+            .collect()
+    }
+
+    pub fn root(&self) -> Self {
+        Self {
+            segments: self.segments[..1].to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for Href {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.segments {
+            write!(f, "/{}", segment.name)?;
+            if let Some(id) = segment.id {
+                write!(f, "/{}", id)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Href {
+    type Err = Error;
+
+    fn from_str(href: &str) -> Result<Self, Self::Err> {
+        let path = href
+            .strip_prefix('/')
+            .ok_or_else(|| Error::InvalidHref(href.to_owned()))?;
+        let mut segments: Vec<HrefSegment> = Vec::new();
+        for part in path.split('/') {
+            if part.is_empty() {
+                return Err(Error::InvalidHref(href.to_owned()));
+            }
+            match (part.parse::<u32>(), segments.last_mut()) {
+                (Ok(id), Some(segment)) if segment.id.is_none() => segment.id = Some(id),
+                (Ok(_), _) => return Err(Error::InvalidHref(href.to_owned())),
+                (Err(_), _) => segments.push(HrefSegment {
+                    name: part.to_owned(),
+                    id: None,
+                }),
+            }
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl From<Href> for String {
+    fn from(href: Href) -> Self {
+        href.to_string()
+    }
+}
+
+impl From<&Href> for String {
+    fn from(href: &Href) -> Self {
+        href.to_string()
+    }
+}
+
+impl Serialize for Href {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut object = serializer.serialize_struct("Href", 1)?;
+        object.serialize_field("href", &self.to_string())?;
+        object.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Href {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct HrefObject {
+            href: String,
+        }
+
+        let object = HrefObject::deserialize(deserializer)?;
+        object.href.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -1,41 +1,223 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[macro_use]
+mod macros;
+
+pub mod command;
+
+mod area;
+mod body;
+mod button;
+mod device;
+mod href;
+mod occupancy;
+mod project;
+mod server;
+mod status;
+mod timeclock;
+mod zone;
+
+pub use area::{Area, AreaTree};
+pub use body::{Body, ExceptionDetail};
+pub use button::{Button, ButtonEvent, ButtonEventType, ButtonGroup, ButtonStatus, VirtualButton};
+pub use device::{Device, DeviceType, Firmware, FirmwareImage, FirmwarePackage, Timestamp};
+pub use href::{Href, HrefSegment};
+pub use occupancy::{
+    AssociatedArea, AssociatedSensor, OccupancyGroup, OccupancyGroupStatus, OccupancyStatus,
+};
+pub use project::{MasterDeviceList, Project};
+pub use server::{Endpoint, PingResponse, Server};
+pub use status::{LeapError, StatusCode};
+pub use timeclock::{
+    upcoming_events, Date, DaysOfWeek, EnabledState, Location, ScheduleType, TimeOfDay, Timeclock,
+    TimeclockEvent, TimeclockEventType, UpcomingEvent,
+};
+pub use zone::{
+    ColorTuningStatus, ControlType, FanSpeed, HsvTuningLevel, SwitchedLevel, WhiteTuningLevel,
+    ZoneCategory, ZoneDefinition, ZoneStatus,
+};
+
+#[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Message {
     pub communique_type: CommuniqueType,
     pub header: Header,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub body: Option<serde_json::Value>,
+    pub body: Option<Body>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawMessage {
+    communique_type: CommuniqueType,
+    header: Header,
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawMessage::deserialize(deserializer)?;
+        let body = raw
+            .body
+            .map(|body| Body::from_value(raw.header.message_body_type.as_deref(), body));
+        Ok(Message {
+            communique_type: raw.communique_type,
+            header: raw.header,
+            body,
+        })
+    }
 }
 
 impl Message {
-    pub fn new(communique_type: CommuniqueType, url: String) -> Self {
+    pub fn new(communique_type: CommuniqueType, url: impl Into<String>) -> Self {
         Self {
             communique_type,
-            header: Header::new(url).with_client_tag("casita".to_owned()),
+            header: Header::new(url.into()).with_client_tag("casita".to_owned()),
             body: None,
         }
     }
+
+    pub fn with_body(mut self, body: Body) -> Self {
+        if let Some(body_type) = body.message_body_type() {
+            self.header.message_body_type = Some(body_type.to_owned());
+        }
+        self.body = Some(body);
+        self
+    }
+
+    pub fn error(&self) -> Option<LeapError> {
+        LeapError::from_message(self)
+    }
+
+    pub fn into_result(self) -> crate::Result<Self> {
+        match self.error() {
+            Some(err) => Err(err.into()),
+            None => Ok(self),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum CommuniqueType {
     ReadRequest,
     ReadResponse,
+    CreateRequest,
+    CreateResponse,
+    UpdateRequest,
+    UpdateResponse,
+    DeleteRequest,
+    DeleteResponse,
     SubscribeRequest,
     SubscribeResponse,
-    UpdateResponse,
+    UnsubscribeRequest,
+    UnsubscribeResponse,
+    ExceptionResponse,
+    Unknown(String),
+}
+
+impl CommuniqueType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CommuniqueType::ReadRequest => "ReadRequest",
+            CommuniqueType::ReadResponse => "ReadResponse",
+            CommuniqueType::CreateRequest => "CreateRequest",
+            CommuniqueType::CreateResponse => "CreateResponse",
+            CommuniqueType::UpdateRequest => "UpdateRequest",
+            CommuniqueType::UpdateResponse => "UpdateResponse",
+            CommuniqueType::DeleteRequest => "DeleteRequest",
+            CommuniqueType::DeleteResponse => "DeleteResponse",
+            CommuniqueType::SubscribeRequest => "SubscribeRequest",
+            CommuniqueType::SubscribeResponse => "SubscribeResponse",
+            CommuniqueType::UnsubscribeRequest => "UnsubscribeRequest",
+            CommuniqueType::UnsubscribeResponse => "UnsubscribeResponse",
+            CommuniqueType::ExceptionResponse => "ExceptionResponse",
+            CommuniqueType::Unknown(other) => other,
+        }
+    }
+
+    pub fn is_request(&self) -> bool {
+        match self {
+            CommuniqueType::Unknown(other) => other.ends_with("Request"),
+            _ => self.expected_response_type().is_some(),
+        }
+    }
+
+    pub fn is_response(&self) -> bool {
+        self.as_str().ends_with("Response")
+    }
+
+    pub fn expected_response_type(&self) -> Option<CommuniqueType> {
+        match self {
+            CommuniqueType::ReadRequest => Some(CommuniqueType::ReadResponse),
+            CommuniqueType::CreateRequest => Some(CommuniqueType::CreateResponse),
+            CommuniqueType::UpdateRequest => Some(CommuniqueType::UpdateResponse),
+            CommuniqueType::DeleteRequest => Some(CommuniqueType::DeleteResponse),
+            CommuniqueType::SubscribeRequest => Some(CommuniqueType::SubscribeResponse),
+            CommuniqueType::UnsubscribeRequest => Some(CommuniqueType::UnsubscribeResponse),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for CommuniqueType {
+    fn from(value: &str) -> Self {
+        match value {
+            "ReadRequest" => CommuniqueType::ReadRequest,
+            "ReadResponse" => CommuniqueType::ReadResponse,
+            "CreateRequest" => CommuniqueType::CreateRequest,
+            "CreateResponse" => CommuniqueType::CreateResponse,
+            "UpdateRequest" => CommuniqueType::UpdateRequest,
+            "UpdateResponse" => CommuniqueType::UpdateResponse,
+            "DeleteRequest" => CommuniqueType::DeleteRequest,
+            "DeleteResponse" => CommuniqueType::DeleteResponse,
+            "SubscribeRequest" => CommuniqueType::SubscribeRequest,
+            "SubscribeResponse" => CommuniqueType::SubscribeResponse,
+            "UnsubscribeRequest" => CommuniqueType::UnsubscribeRequest,
+            "UnsubscribeResponse" => CommuniqueType::UnsubscribeResponse,
+            "ExceptionResponse" => CommuniqueType::ExceptionResponse,
+            other => CommuniqueType::Unknown(other.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for CommuniqueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CommuniqueType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl<'de> Deserialize<'de> for CommuniqueType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(CommuniqueType::from(value.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Header {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub status_code: Option<String>,
+    pub status_code: Option<StatusCode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_body_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Header {
@@ -44,6 +226,10 @@ impl Header {
             url,
             status_code: None,
             client_tag: None,
+            message_body_type: None,
+            content_type: None,
+            paging: None,
+            extra: Map::new(),
         }
     }
 
@@ -51,4 +237,54 @@ impl Header {
         self.client_tag = Some(tag);
         self
     }
+
+    pub fn href(&self) -> crate::Result<Href> {
+        self.url.parse()
+    }
+
+    pub fn with_status_code(mut self, status_code: StatusCode) -> Self {
+        self.status_code = Some(status_code);
+        self
+    }
+
+    pub fn with_message_body_type(mut self, body_type: String) -> Self {
+        self.message_body_type = Some(body_type);
+        self
+    }
+
+    pub fn with_content_type(mut self, content_type: String) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    pub fn with_paging(mut self, paging: Paging) -> Self {
+        self.paging = Some(paging);
+        self
+    }
+
+    pub fn with_field(mut self, name: String, value: Value) -> Self {
+        self.extra.insert(name, value);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Paging {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Paging {
+    pub fn new(limit: u32, offset: u32) -> Self {
+        Self {
+            limit: Some(limit),
+            offset: Some(offset),
+            extra: Map::new(),
+        }
+    }
 }
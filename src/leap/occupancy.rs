@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+string_enum! {
+    pub enum OccupancyStatus {
+        Occupied => "Occupied",
+        Unoccupied => "Unoccupied",
+    }
+}
+
+impl OccupancyStatus {
+    pub fn is_occupied(&self) -> bool {
+        *self == OccupancyStatus::Occupied
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct OccupancyGroup {
+    #[serde(flatten)]
+    pub href: Href,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_sensors: Vec<AssociatedSensor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub associated_areas: Vec<AssociatedArea>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programming_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programming_model: Option<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl OccupancyGroup {
+    pub fn area(&self) -> Option<&Href> {
+        self.associated_areas
+            .first()
+            .map(|associated| &associated.area)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssociatedSensor {
+    pub occupancy_sensor: Href,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct AssociatedArea {
+    pub area: Href,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct OccupancyGroupStatus {
+    #[serde(flatten)]
+    pub href: Href,
+    pub occupancy_group: Href,
+    pub occupancy_status: OccupancyStatus,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
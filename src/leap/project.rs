@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Project {
+    #[serde(flatten)]
+    pub href: Href,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master_device_list: Option<MasterDeviceList>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Project {
+    pub fn master_devices(&self) -> &[Href] {
+        self.master_device_list
+            .as_ref()
+            .map(|list| list.devices.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MasterDeviceList {
+    #[serde(default)]
+    pub devices: Vec<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
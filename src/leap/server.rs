@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PingResponse {
+    #[serde(rename = "LEAPVersion")]
+    pub leap_version: f64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Server {
+    #[serde(flatten)]
+    pub href: Href,
+    #[serde(rename = "Type", default, skip_serializing_if = "Option::is_none")]
+    pub server_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_state: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_interfaces: Vec<Href>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<Endpoint>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Endpoint {
+    pub protocol: String,
+    pub port: u16,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use super::{Body, CommuniqueType, Message};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StatusCode {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl StatusCode {
+    pub fn new(code: u16, reason: impl Into<String>) -> Self {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn parse(status: &str) -> Self {
+        let status = status.trim();
+        let (code, reason) = status.split_once(' ').unwrap_or((status, ""));
+        match code.parse() {
+            Ok(code) => Self::new(code, reason.trim()),
+            Err(_) => Self::new(0, status),
+        }
+    }
+
+    pub fn is_informational(&self) -> bool {
+        (100..200).contains(&self.code)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+
+    pub fn is_redirection(&self) -> bool {
+        (300..400).contains(&self.code)
+    }
+
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.code)
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.code)
+    }
+}
+
+impl fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.code, self.reason.is_empty()) {
+            (0, _) => f.write_str(&self.reason),
+            (code, true) => write!(f, "{}", code),
+            (code, false) => write!(f, "{} {}", code, self.reason),
+        }
+    }
+}
+
+impl Serialize for StatusCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = String::deserialize(deserializer)?;
+        Ok(StatusCode::parse(&status))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LeapError {
+    pub communique_type: CommuniqueType,
+    pub url: String,
+    pub status: Option<StatusCode>,
+    pub message: Option<String>,
+}
+
+impl LeapError {
+    pub(crate) fn from_message(msg: &Message) -> Option<Self> {
+        let status = msg.header.status_code.clone();
+        let failed = msg.communique_type == CommuniqueType::ExceptionResponse
+            || status.as_ref().is_some_and(|status| !status.is_success());
+        if !failed {
+            return None;
+        }
+        let message = match &msg.body {
+            Some(Body::ExceptionDetail(detail)) => Some(detail.message.clone()),
+            Some(Body::Raw(body)) => body["Message"].as_str().map(str::to_owned),
+            _ => None,
+        };
+        Some(Self {
+            communique_type: msg.communique_type.clone(),
+            url: msg.header.url.clone(),
+            status,
+            message,
+        })
+    }
+}
+
+impl fmt::Display for LeapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} for {}", self.communique_type, self.url)?;
+        if let Some(status) = &self.status {
+            write!(f, " failed with {}", status)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LeapError {}
@@ -0,0 +1,444 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::f64::consts::PI;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{Body, CommuniqueType, Href, Message};
+use crate::{Error, Result};
+
+string_enum! {
+    pub enum EnabledState {
+        Enabled => "Enabled",
+        Disabled => "Disabled",
+    }
+}
+
+string_enum! {
+    pub enum TimeclockEventType {
+        FixedTime => "FixedTime",
+        Sunrise => "Sunrise",
+        Sunset => "Sunset",
+    }
+}
+
+string_enum! {
+    pub enum ScheduleType {
+        DayOfWeek => "DayOfWeek",
+        SpecificDates => "SpecificDates",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Timeclock {
+    #[serde(flatten)]
+    pub href: Href,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_state: Option<EnabledState>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeclock_events: Vec<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimeclockEvent {
+    #[serde(flatten)]
+    pub href: Option<Href>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_state: Option<EnabledState>,
+    pub event_type: TimeclockEventType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_of_day: Option<TimeOfDay>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_minutes: Option<i32>,
+    pub schedule_type: ScheduleType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_of_week: Option<DaysOfWeek>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub specific_dates: Vec<Date>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programming_model: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub virtual_button: Option<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DaysOfWeek {
+    #[serde(default)]
+    pub sunday: bool,
+    #[serde(default)]
+    pub monday: bool,
+    #[serde(default)]
+    pub tuesday: bool,
+    #[serde(default)]
+    pub wednesday: bool,
+    #[serde(default)]
+    pub thursday: bool,
+    #[serde(default)]
+    pub friday: bool,
+    #[serde(default)]
+    pub saturday: bool,
+}
+
+impl DaysOfWeek {
+    pub fn every_day() -> Self {
+        Self {
+            sunday: true,
+            monday: true,
+            tuesday: true,
+            wednesday: true,
+            thursday: true,
+            friday: true,
+            saturday: true,
+        }
+    }
+
+    pub fn contains(&self, weekday: u32) -> bool {
+        match weekday {
+            0 => self.sunday,
+            1 => self.monday,
+            2 => self.tuesday,
+            3 => self.wednesday,
+            4 => self.thursday,
+            5 => self.friday,
+            6 => self.saturday,
+            _ => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..7).all(|weekday| !self.contains(weekday))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "PascalCase")]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self> {
+        let date = Self { year, month, day };
+        if Date::from_days(date.days()) != date {
+            return Err(Error::InvalidTimeclock(format!("invalid date {}", date)));
+        }
+        Ok(date)
+    }
+
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+
+    pub fn weekday(&self) -> u32 {
+        (self.days() + 4).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+    #[serde(default)]
+    pub second: u8,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u8, minute: u8) -> Self {
+        Self {
+            hour,
+            minute,
+            second: 0,
+        }
+    }
+
+    pub fn from_minutes(minutes: i64) -> Self {
+        let minutes = minutes.rem_euclid(24 * 60);
+        Self::new((minutes / 60) as u8, (minutes % 60) as u8)
+    }
+
+    pub fn minutes(&self) -> i64 {
+        i64::from(self.hour) * 60 + i64::from(self.minute)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.hour < 24 && self.minute < 60 && self.second < 60
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset_minutes: i32,
+}
+
+impl Location {
+    pub fn new(latitude: f64, longitude: f64, utc_offset_minutes: i32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            utc_offset_minutes,
+        }
+    }
+
+    pub fn now(&self) -> (Date, TimeOfDay) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default()
+            + i64::from(self.utc_offset_minutes) * 60;
+        let time = secs.rem_euclid(86_400);
+        let mut time_of_day = TimeOfDay::from_minutes(time / 60);
+        time_of_day.second = (time % 60) as u8;
+        (Date::from_days(secs.div_euclid(86_400)), time_of_day)
+    }
+
+    pub fn sun_times(&self, date: Date) -> Option<(TimeOfDay, TimeOfDay)> {
+        let radians = PI / 180.0;
+        let julian_noon = date.days() as f64 + 2_440_588.0;
+        let n = (julian_noon - 2_451_545.0 + 0.0008).round();
+        let mean_noon = n - self.longitude / 360.0;
+        let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0);
+        let m = anomaly * radians;
+        let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+        let ecliptic = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0) * radians;
+        let transit = 2_451_545.0 + mean_noon + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic).sin();
+        let declination = (ecliptic.sin() * (23.4397 * radians).sin()).asin();
+        let latitude = self.latitude * radians;
+        let cos_hour_angle = ((-0.833 * radians).sin() - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let hour_angle = cos_hour_angle.acos() / radians / 360.0;
+        let local = |julian: f64| {
+            let minutes = (julian - (julian_noon - 0.5)) * 24.0 * 60.0;
+            TimeOfDay::from_minutes(minutes.round() as i64 + i64::from(self.utc_offset_minutes))
+        };
+        Some((local(transit - hour_angle), local(transit + hour_angle)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpcomingEvent {
+    pub date: Date,
+    pub time: TimeOfDay,
+    pub event: Option<Href>,
+    pub name: String,
+    pub event_type: TimeclockEventType,
+}
+
+impl TimeclockEvent {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_state != Some(EnabledState::Disabled)
+    }
+
+    pub fn runs_on(&self, date: Date) -> bool {
+        match self.schedule_type {
+            ScheduleType::DayOfWeek => self
+                .days_of_week
+                .is_some_and(|days| days.contains(date.weekday())),
+            ScheduleType::SpecificDates => self.specific_dates.contains(&date),
+            ScheduleType::Unknown(_) => false,
+        }
+    }
+
+    pub fn time_on(&self, date: Date, location: Option<&Location>) -> Option<(Date, TimeOfDay)> {
+        let sun_time = |sunrise: bool| {
+            let (rise, set) = location?.sun_times(date)?;
+            let time = if sunrise { rise } else { set };
+            let minutes = time.minutes() + i64::from(self.offset_minutes.unwrap_or_default());
+            Some((
+                date.add_days(minutes.div_euclid(24 * 60)),
+                TimeOfDay::from_minutes(minutes),
+            ))
+        };
+        match self.event_type {
+            TimeclockEventType::FixedTime => self.time_of_day.map(|time| (date, time)),
+            TimeclockEventType::Sunrise => sun_time(true),
+            TimeclockEventType::Sunset => sun_time(false),
+            TimeclockEventType::Unknown(_) => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(Error::InvalidTimeclock(reason));
+        match (&self.event_type, &self.time_of_day) {
+            (TimeclockEventType::FixedTime, None) => {
+                return invalid(format!("{} needs a TimeOfDay", self.name))
+            }
+            (_, Some(time)) if !time.is_valid() => {
+                return invalid(format!("invalid time of day {}", time))
+            }
+            (TimeclockEventType::Unknown(event_type), _) => {
+                return invalid(format!("unknown event type {}", event_type))
+            }
+            _ => {}
+        }
+        if self
+            .offset_minutes
+            .is_some_and(|offset| offset.abs() > 12 * 60)
+        {
+            return invalid("offsets must be within 12 hours".to_owned());
+        }
+        match &self.schedule_type {
+            ScheduleType::DayOfWeek if self.days_of_week.unwrap_or_default().is_empty() => {
+                invalid(format!("{} does not run on any day", self.name))
+            }
+            ScheduleType::SpecificDates if self.specific_dates.is_empty() => {
+                invalid(format!("{} does not have any dates", self.name))
+            }
+            ScheduleType::Unknown(schedule_type) => {
+                invalid(format!("unknown schedule type {}", schedule_type))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn create_request(&self, timeclock: &Href) -> Result<Message> {
+        self.validate()?;
+        if self.href.is_some() {
+            return Err(Error::InvalidTimeclock(format!(
+                "{} already exists",
+                self.name
+            )));
+        }
+        if timeclock.kind() != "timeclock" || timeclock.id().is_none() {
+            return Err(Error::InvalidTimeclock(format!(
+                "{} is not a timeclock",
+                timeclock
+            )));
+        }
+        let event = TimeclockEvent {
+            parent: Some(timeclock.clone()),
+            ..self.clone()
+        };
+        Ok(Message::new(
+            CommuniqueType::CreateRequest,
+            timeclock.root().child("timeclockevent"),
+        )
+        .with_body(Body::OneTimeclockEventDefinition(event)))
+    }
+
+    pub fn update_request(&self) -> Result<Message> {
+        self.validate()?;
+        let href = self.require_href()?;
+        Ok(Message::new(CommuniqueType::UpdateRequest, href)
+            .with_body(Body::OneTimeclockEventDefinition(self.clone())))
+    }
+
+    pub fn delete_request(&self) -> Result<Message> {
+        let href = self.require_href()?;
+        Ok(Message::new(CommuniqueType::DeleteRequest, href))
+    }
+
+    fn require_href(&self) -> Result<&Href> {
+        match &self.href {
+            Some(href) if href.kind() == "timeclockevent" && href.id().is_some() => Ok(href),
+            Some(href) => Err(Error::InvalidTimeclock(format!(
+                "{} is not a timeclock event",
+                href
+            ))),
+            None => Err(Error::InvalidTimeclock(format!(
+                "{} has not been created yet",
+                self.name
+            ))),
+        }
+    }
+}
+
+pub fn upcoming_events(
+    events: &[TimeclockEvent],
+    start: Date,
+    now: TimeOfDay,
+    days: u32,
+    location: Option<&Location>,
+) -> Vec<UpcomingEvent> {
+    let end = start.add_days(i64::from(days));
+    let mut upcoming = Vec::new();
+    for offset in -1..=i64::from(days) {
+        let scheduled = start.add_days(offset);
+        for event in events {
+            if !event.is_enabled() || !event.runs_on(scheduled) {
+                continue;
+            }
+            let (date, time) = match event.time_on(scheduled, location) {
+                Some(occurrence) => occurrence,
+                None => {
+                    log::debug!("No time for {} on {}", event.name, scheduled);
+                    continue;
+                }
+            };
+            if date < start || date >= end || (date == start && time < now) {
+                continue;
+            }
+            upcoming.push(UpcomingEvent {
+                date,
+                time,
+                event: event.href.clone(),
+                name: event.name.clone(),
+                event_type: event.event_type.clone(),
+            });
+        }
+    }
+    upcoming.sort_by_key(|event| (event.date, event.time));
+    upcoming
+}
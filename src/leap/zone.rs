@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Href;
+
+string_enum! {
+    pub enum ControlType {
+        Dimmed => "Dimmed",
+        Switched => "Switched",
+        FanSpeed => "FanSpeed",
+        Shade => "Shade",
+        ShadeWithTilt => "ShadeWithTilt",
+        Tilt => "Tilt",
+        WhiteTune => "WhiteTune",
+        SpectrumTune => "SpectrumTune",
+        ColorTune => "ColorTune",
+        Receptacle => "Receptacle",
+        CCO => "CCO",
+    }
+}
+
+string_enum! {
+    pub enum FanSpeed {
+        Off => "Off",
+        Low => "Low",
+        Medium => "Medium",
+        MediumHigh => "MediumHigh",
+        High => "High",
+    }
+}
+
+string_enum! {
+    pub enum SwitchedLevel {
+        On => "On",
+        Off => "Off",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ZoneDefinition {
+    #[serde(flatten)]
+    pub href: Href,
+    pub name: String,
+    pub control_type: ControlType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<ZoneCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub associated_area: Option<Href>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ZoneCategory {
+    #[serde(rename = "Type")]
+    pub category_type: String,
+    #[serde(default)]
+    pub is_light: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ZoneStatus {
+    #[serde(flatten)]
+    pub href: Href,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub switched_level: Option<SwitchedLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_speed: Option<FanSpeed>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tilt: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_tuning_status: Option<ColorTuningStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vibrancy: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<Href>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_accuracy: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ColorTuningStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub white_tuning_level: Option<WhiteTuningLevel>,
+    #[serde(
+        rename = "HSVTuningLevel",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hsv_tuning_level: Option<HsvTuningLevel>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WhiteTuningLevel {
+    pub kelvin: u16,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct HsvTuningLevel {
+    pub hue: u16,
+    pub saturation: u8,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
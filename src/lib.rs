@@ -1,5 +1,10 @@
 pub mod client;
+pub mod codec;
+pub mod error;
 pub mod lap;
 pub mod leap;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use client::*;
+pub use error::{Error, Result};
@@ -0,0 +1,372 @@
+use async_channel::Sender;
+use futures::{SinkExt, StreamExt};
+use openssl::{
+    asn1::Asn1Time,
+    bn::{BigNum, MsbOption},
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    rsa::Rsa,
+    ssl::{Ssl, SslContext, SslContextBuilder, SslMethod, SslVerifyMode},
+    x509::{
+        extension::{BasicConstraints, KeyUsage, SubjectAlternativeName},
+        X509NameBuilder, X509,
+    },
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_openssl::SslStream;
+use tokio_util::codec::Framed;
+
+use crate::{codec::LeapCodec, Certs, Error, Result};
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Responder = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+struct Identity {
+    cert: X509,
+    rsa: Rsa<Private>,
+}
+
+impl Identity {
+    fn key(&self) -> Result<PKey<Private>> {
+        Ok(PKey::from_rsa(self.rsa.clone())?)
+    }
+}
+
+struct Shared {
+    responses: Mutex<HashMap<String, Responder>>,
+    received: Mutex<Vec<Value>>,
+    received_tx: watch::Sender<usize>,
+    received_rx: watch::Receiver<usize>,
+    connections: Mutex<Vec<Sender<Value>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+pub struct MockBridge {
+    addr: SocketAddr,
+    ca: Identity,
+    client: Identity,
+    shared: Arc<Shared>,
+    accept_task: JoinHandle<()>,
+}
+
+impl MockBridge {
+    pub async fn start() -> Result<Self> {
+        let ca = generate_identity("casita mock CA", None)?;
+        let server = generate_identity("localhost", Some(&ca))?;
+        let client = generate_identity("casita mock client", Some(&ca))?;
+
+        let mut context = SslContextBuilder::new(SslMethod::tls_server())?;
+        context.set_certificate(&server.cert)?;
+        let server_key = server.key()?;
+        context.set_private_key(&server_key)?;
+        context.cert_store_mut().add_cert(ca.cert.clone())?;
+        context.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        let context = context.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let (received_tx, received_rx) = watch::channel(0);
+        let shared = Arc::new(Shared {
+            responses: Mutex::new(HashMap::new()),
+            received: Mutex::new(Vec::new()),
+            received_tx,
+            received_rx,
+            connections: Mutex::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+        });
+        let accept_task = tokio::spawn(accept_context(listener, context, shared.clone()));
+
+        Ok(Self {
+            addr,
+            ca,
+            client,
+            shared,
+            accept_task,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn host(&self) -> String {
+        self.addr.to_string()
+    }
+
+    pub fn client_certs(&self) -> Result<Certs> {
+        Certs::from_pem(
+            &self.ca.cert.to_pem()?,
+            &self.client.cert.to_pem()?,
+            &self.client.rsa.private_key_to_pem()?,
+        )
+    }
+
+    pub fn write_client_certs(&self, dir: &Path) -> Result<()> {
+        std::fs::write(dir.join("caseta-bridge.crt"), self.ca.cert.to_pem()?)?;
+        std::fs::write(dir.join("caseta.crt"), self.client.cert.to_pem()?)?;
+        std::fs::write(
+            dir.join("caseta.key"),
+            self.client.rsa.private_key_to_pem()?,
+        )?;
+        Ok(())
+    }
+
+    pub fn respond(&self, url: impl Into<String>, response: Value) {
+        self.respond_with(url, move |_| response.clone());
+    }
+
+    pub fn respond_with<F>(&self, url: impl Into<String>, responder: F)
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        self.shared
+            .responses
+            .lock()
+            .unwrap()
+            .insert(url.into(), Arc::new(responder));
+    }
+
+    pub async fn push(&self, msg: Value) -> Result<()> {
+        let connections = self.shared.connections.lock().unwrap().clone();
+        if connections.is_empty() {
+            return Err(Error::NotConnected);
+        }
+        for connection in connections {
+            let _ = connection.send(msg.clone()).await;
+        }
+        Ok(())
+    }
+
+    pub fn close_connections(&self) {
+        for connection in self.shared.connections.lock().unwrap().drain(..) {
+            connection.close();
+        }
+    }
+
+    pub fn received(&self) -> Vec<Value> {
+        self.shared.received.lock().unwrap().clone()
+    }
+
+    pub async fn wait_for_request(&self, url: &str) -> Result<Value> {
+        let mut received_rx = self.shared.received_rx.clone();
+        let wait = async {
+            loop {
+                let found = self
+                    .received()
+                    .into_iter()
+                    .find(|msg| msg["Header"]["Url"].as_str() == Some(url));
+                if let Some(msg) = found {
+                    return Ok(msg);
+                }
+                if received_rx.changed().await.is_err() {
+                    return Err(Error::NotConnected);
+                }
+            }
+        };
+        tokio::time::timeout(WAIT_TIMEOUT, wait)
+            .await
+            .map_err(|_| Error::Timeout)?
+    }
+
+    pub async fn wait_for_connection(&self) -> Result<()> {
+        let wait = async {
+            while self.shared.connections.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(WAIT_TIMEOUT, wait)
+            .await
+            .map_err(|_| Error::Timeout)
+    }
+}
+
+impl Drop for MockBridge {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        for task in self.shared.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+}
+
+async fn accept_context(listener: TcpListener, context: SslContext, shared: Arc<Shared>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                log::error!("Mock bridge failed to accept: {}", err);
+                return;
+            }
+        };
+        let task = tokio::spawn(connection_context(stream, context.clone(), shared.clone()));
+        shared.tasks.lock().unwrap().push(task);
+    }
+}
+
+async fn connection_context(stream: TcpStream, context: SslContext, shared: Arc<Shared>) {
+    if let Err(err) = serve_connection(stream, &context, &shared).await {
+        log::debug!("Mock bridge connection closed: {}", err);
+    }
+}
+
+async fn serve_connection(stream: TcpStream, context: &SslContext, shared: &Shared) -> Result<()> {
+    let mut stream = SslStream::new(Ssl::new(context)?, stream)?;
+    Pin::new(&mut stream).accept().await?;
+    let mut framed = Framed::new(stream, LeapCodec::new());
+
+    let (push_tx, push_rx) = async_channel::unbounded();
+    shared.connections.lock().unwrap().push(push_tx.clone());
+    let result = loop {
+        tokio::select! {
+            msg = framed.next() => {
+                let msg = match msg {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(err)) => break Err(err),
+                    None => break Ok(()),
+                };
+                let response = shared.response_for(&msg);
+                shared.record(msg);
+                if let Some(response) = response {
+                    if let Err(err) = framed.send(response).await {
+                        break Err(err);
+                    }
+                }
+            },
+            msg = push_rx.recv() => {
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    Err(_) => break Ok(()),
+                };
+                if let Err(err) = framed.send(msg).await {
+                    break Err(err);
+                }
+            },
+        }
+    };
+    push_tx.close();
+    shared
+        .connections
+        .lock()
+        .unwrap()
+        .retain(|connection| !connection.is_closed());
+    result
+}
+
+impl Shared {
+    fn record(&self, msg: Value) {
+        let count = {
+            let mut received = self.received.lock().unwrap();
+            received.push(msg);
+            received.len()
+        };
+        let _ = self.received_tx.send(count);
+    }
+
+    fn response_for(&self, request: &Value) -> Option<Value> {
+        let url = request["Header"]["Url"].as_str()?;
+        let scripted = self.responses.lock().unwrap().get(url).cloned();
+        let mut response = match scripted {
+            Some(responder) => responder(request),
+            None => default_response(request, url),
+        };
+        if response["Header"]["Url"].is_null() {
+            response["Header"]["Url"] = json!(url);
+        }
+        if !request["Header"]["ClientTag"].is_null() {
+            response["Header"]["ClientTag"] = request["Header"]["ClientTag"].clone();
+        }
+        Some(response)
+    }
+}
+
+fn default_response(request: &Value, url: &str) -> Value {
+    match (request["CommuniqueType"].as_str(), url) {
+        (Some("ReadRequest"), "/server/1/status/ping") => json!({
+            "CommuniqueType": "ReadResponse",
+            "Header": {
+                "MessageBodyType": "OnePingResponse",
+                "StatusCode": "200 OK",
+            },
+            "Body": { "PingResponse": { "LEAPVersion": 1.115 } },
+        }),
+        (Some("SubscribeRequest"), _) => json!({
+            "CommuniqueType": "SubscribeResponse",
+            "Header": { "StatusCode": "200 OK" },
+        }),
+        (Some("UnsubscribeRequest"), _) => json!({
+            "CommuniqueType": "UnsubscribeResponse",
+            "Header": { "StatusCode": "200 OK" },
+        }),
+        _ => json!({
+            "CommuniqueType": "ExceptionResponse",
+            "Header": {
+                "MessageBodyType": "ExceptionDetail",
+                "StatusCode": "404 NotFound",
+            },
+            "Body": { "Message": "The requested resource does not exist." },
+        }),
+    }
+}
+
+fn generate_identity(common_name: &str, issuer: Option<&Identity>) -> Result<Identity> {
+    let rsa = Rsa::generate(2048)?;
+    let key = PKey::from_rsa(rsa.clone())?;
+
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_text("CN", common_name)?;
+    let name = name.build();
+
+    let mut serial = BigNum::new()?;
+    serial.rand(64, MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let serial = serial.to_asn1_integer()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(1)?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(&name)?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+
+    match issuer {
+        Some(issuer) => {
+            builder.set_issuer_name(issuer.cert.subject_name())?;
+            let san = SubjectAlternativeName::new()
+                .dns("localhost")
+                .ip("127.0.0.1")
+                .build(&builder.x509v3_context(Some(&issuer.cert), None))?;
+            builder.append_extension(san)?;
+            let issuer_key = issuer.key()?;
+            builder.sign(&issuer_key, MessageDigest::sha256())?;
+        }
+        None => {
+            builder.set_issuer_name(&name)?;
+            builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+            builder.append_extension(
+                KeyUsage::new()
+                    .critical()
+                    .key_cert_sign()
+                    .crl_sign()
+                    .build()?,
+            )?;
+            builder.sign(&key, MessageDigest::sha256())?;
+        }
+    }
+
+    Ok(Identity {
+        cert: builder.build(),
+        rsa,
+    })
+}
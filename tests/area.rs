@@ -0,0 +1,100 @@
+mod common;
+
+use casita::{
+    leap::{AreaTree, Body, Device, DeviceType, Href, Message},
+    testing::MockBridge,
+};
+use common::{connect, fixture};
+
+fn tree() -> AreaTree {
+    let msg: Message = serde_json::from_value(fixture("area_multiple")).unwrap();
+    match msg.body {
+        Some(Body::MultipleAreaDefinition(areas)) => AreaTree::new(areas),
+        other => panic!("expected areas, got {:?}", other),
+    }
+}
+
+fn names<'a>(areas: impl IntoIterator<Item = &'a casita::leap::Area>) -> Vec<&'a str> {
+    areas.into_iter().map(|area| area.name.as_str()).collect()
+}
+
+#[test]
+fn parses_area_associations() {
+    let tree = tree();
+    let kitchen = tree.get(&Href::area(3)).unwrap();
+    assert_eq!(kitchen.is_leaf, Some(true));
+    assert_eq!(kitchen.associated_zones, vec![Href::zone(1), Href::zone(2)]);
+    assert_eq!(
+        kitchen.associated_occupancy_groups,
+        vec![Href::occupancy_group(2)]
+    );
+    assert_eq!(tree.zones(&Href::area(4)), &[Href::zone(3)]);
+}
+
+#[test]
+fn builds_area_hierarchy() {
+    let tree = tree();
+    assert_eq!(tree.len(), 5);
+    assert_eq!(tree.root().unwrap().name, "Home");
+    assert_eq!(
+        names(tree.children(&Href::area(2))),
+        vec!["Kitchen", "Hallway"]
+    );
+    assert_eq!(tree.parent(&Href::area(5)).unwrap().name, "Home");
+
+    let walked: Vec<(usize, &str)> = tree
+        .walk()
+        .into_iter()
+        .map(|(depth, area)| (depth, area.name.as_str()))
+        .collect();
+    assert_eq!(
+        walked,
+        vec![
+            (0, "Home"),
+            (1, "Main Floor"),
+            (2, "Kitchen"),
+            (2, "Hallway"),
+            (1, "Upstairs"),
+        ]
+    );
+}
+
+#[test]
+fn finds_paths_for_zones_and_devices() {
+    let tree = tree();
+    assert_eq!(
+        names(tree.zone_path(&Href::zone(3))),
+        vec!["Home", "Main Floor", "Hallway"]
+    );
+    assert!(tree.zone_path(&Href::zone(99)).is_empty());
+
+    let device = Device {
+        href: Href::device(4),
+        name: "Pico".to_owned(),
+        fully_qualified_name: vec![],
+        device_type: DeviceType::Pico3ButtonRaiseLower,
+        serial_number: None,
+        model_number: None,
+        local_zones: vec![],
+        button_groups: vec![],
+        associated_area: Some(Href::area(3)),
+        firmware_image: None,
+        device_firmware_package: None,
+        extra: Default::default(),
+    };
+    assert_eq!(
+        names(tree.device_path(&device)),
+        vec!["Home", "Main Floor", "Kitchen"]
+    );
+}
+
+#[tokio::test]
+async fn reads_area_tree_from_bridge() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/area", fixture("area_multiple"));
+    let client = connect(&bridge).await;
+
+    let tree = client.area_tree().await.unwrap();
+    assert_eq!(tree.area_for_zone(&Href::zone(2)).unwrap().name, "Kitchen");
+    client.disconnect().await.unwrap();
+}
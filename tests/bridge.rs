@@ -0,0 +1,197 @@
+mod common;
+
+use casita::{
+    leap::{Body, DeviceType, Href, Message},
+    testing::MockBridge,
+    Error,
+};
+use common::{connect, definition};
+use serde_json::{json, Value};
+
+#[test]
+fn parses_project_and_bridge_device() {
+    let project: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": {
+            "Url": "/project",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OneProjectDefinition",
+        },
+        "Body": {
+            "Project": {
+                "href": "/project",
+                "Name": "Smart Bridge Project",
+                "ProductType": "Lutron Smart Bridge Project",
+                "MasterDeviceList": { "Devices": [{ "href": "/device/1" }] },
+                "Contacts": [{ "href": "/contactinfo/1" }],
+            },
+        },
+    }))
+    .unwrap();
+    match project.body {
+        Some(Body::OneProjectDefinition(project)) => {
+            assert_eq!(project.name, "Smart Bridge Project");
+            assert_eq!(project.master_devices(), &[Href::device(1)]);
+        }
+        other => panic!("expected a project, got {:?}", other),
+    }
+
+    let device: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": {
+            "Url": "/device/1",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OneDeviceDefinition",
+        },
+        "Body": {
+            "Device": {
+                "href": "/device/1",
+                "Name": "Smart Bridge 2",
+                "DeviceType": "SmartBridge",
+                "SerialNumber": 87654321,
+                "ModelNumber": "L-BDG2-WH",
+                "FirmwareImage": {
+                    "Firmware": { "DisplayName": "08.25.17f000" },
+                    "Installed": { "Year": 2021, "Month": 4, "Day": 9, "Hour": 3, "Minute": 2, "Second": 1, "Utc": "0" },
+                },
+                "DeviceFirmwarePackage": { "Package": { "DisplayName": "001.003.004r000" } },
+            },
+        },
+    }))
+    .unwrap();
+    match device.body {
+        Some(Body::OneDeviceDefinition(device)) => {
+            let image = device.firmware_image.unwrap();
+            assert_eq!(image.firmware.display_name, "08.25.17f000");
+            assert_eq!(image.installed.unwrap().year, 2021);
+            assert_eq!(
+                device.device_firmware_package.unwrap().package.display_name,
+                "001.003.004r000"
+            );
+        }
+        other => panic!("expected a device, got {:?}", other),
+    }
+}
+
+fn project(master_devices: &[&str]) -> Value {
+    let devices: Vec<Value> = master_devices
+        .iter()
+        .map(|href| json!({ "href": href }))
+        .collect();
+    definition(
+        "OneProjectDefinition",
+        json!({ "Project": {
+            "href": "/project",
+            "Name": "Mock Bridge Project",
+            "ProductType": "Lutron Smart Bridge Project",
+            "MasterDeviceList": { "Devices": devices },
+        } }),
+    )
+}
+
+fn smart_bridge(href: &str) -> Value {
+    json!({
+        "href": href,
+        "Name": "Smart Bridge",
+        "DeviceType": "SmartBridge",
+        "SerialNumber": 12345678,
+        "ModelNumber": "L-BDG2-WH",
+        "FirmwareImage": { "Firmware": { "DisplayName": "08.25.17f000" } },
+    })
+}
+
+fn pico(href: &str) -> Value {
+    json!({
+        "href": href,
+        "Name": "Pico",
+        "DeviceType": "Pico3ButtonRaiseLower",
+    })
+}
+
+fn servers() -> Value {
+    definition(
+        "MultipleServerDefinition",
+        json!({ "Servers": [{
+            "href": "/server/1",
+            "Type": "LEAP",
+            "EnableState": "Enabled",
+            "Endpoints": [{ "Protocol": "TCP", "Port": 8081 }],
+        }] }),
+    )
+}
+
+#[tokio::test]
+async fn aggregates_bridge_info() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/server", servers());
+    bridge.respond("/project", project(&["/device/1"]));
+    bridge.respond(
+        "/device/1",
+        definition(
+            "OneDeviceDefinition",
+            json!({ "Device": smart_bridge("/device/1") }),
+        ),
+    );
+    let client = connect(&bridge).await;
+
+    let info = client.bridge_info().await.unwrap();
+    assert_eq!(info.leap_version, 1.115);
+    assert_eq!(info.servers[0].endpoints[0].port, 8081);
+    assert_eq!(info.project.name, "Mock Bridge Project");
+    assert_eq!(info.device.device_type, DeviceType::SmartBridge);
+    assert_eq!(info.serial_number(), Some(12345678));
+    assert_eq!(info.model_number(), Some("L-BDG2-WH"));
+    assert_eq!(info.firmware_version(), Some("08.25.17f000"));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn falls_back_to_device_list_for_main_repeater() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/server", servers());
+    bridge.respond("/project", project(&["/device/2"]));
+    bridge.respond(
+        "/device/2",
+        definition(
+            "OneDeviceDefinition",
+            json!({ "Device": pico("/device/2") }),
+        ),
+    );
+    bridge.respond(
+        "/device",
+        definition(
+            "MultipleDeviceDefinition",
+            json!({ "Devices": [pico("/device/2"), smart_bridge("/device/7")] }),
+        ),
+    );
+    let client = connect(&bridge).await;
+
+    let info = client.bridge_info().await.unwrap();
+    assert_eq!(info.device.href, Href::device(7));
+    assert!(bridge
+        .received()
+        .iter()
+        .all(|msg| msg["Header"]["Url"] != "/device/1"));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn reports_missing_main_repeater() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/server", servers());
+    bridge.respond("/project", project(&[]));
+    bridge.respond(
+        "/device",
+        definition(
+            "MultipleDeviceDefinition",
+            json!({ "Devices": [pico("/device/2")] }),
+        ),
+    );
+    let client = connect(&bridge).await;
+
+    assert!(matches!(
+        client.bridge_info().await,
+        Err(Error::MainRepeaterNotFound)
+    ));
+    client.disconnect().await.unwrap();
+}
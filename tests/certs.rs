@@ -0,0 +1,36 @@
+use casita::{testing::MockBridge, Certs, Error};
+
+#[test]
+fn reports_invalid_pem_by_name() {
+    let err = Certs::from_pem(b"not a certificate", b"", b"").err();
+    match &err {
+        Some(Error::InvalidPem { name, .. }) => assert_eq!(*name, "leap_ca_cert"),
+        other => panic!("expected an invalid PEM error, got {:?}", other),
+    }
+    let message = err.unwrap().to_string();
+    assert!(
+        message.starts_with("invalid PEM for leap_ca_cert:"),
+        "{}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn reports_missing_files_as_certificate_errors() {
+    let bridge = MockBridge::start().await.unwrap();
+    let dir = std::env::temp_dir().join(format!("casita-certs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    bridge.write_client_certs(&dir).unwrap();
+    std::fs::remove_file(dir.join("caseta.key")).unwrap();
+
+    let result = Certs::new(
+        dir.join("caseta-bridge.crt"),
+        dir.join("caseta.crt"),
+        dir.join("caseta.key"),
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+    match result {
+        Err(Error::Certificate { path, .. }) => assert_eq!(path, dir.join("caseta.key")),
+        other => panic!("expected a certificate error, got {:?}", other.err()),
+    }
+}
@@ -0,0 +1,68 @@
+use bytes::BytesMut;
+use casita::{codec::LeapCodec, Error};
+use serde_json::json;
+use tokio_util::codec::{Decoder, Encoder};
+
+#[test]
+fn decodes_frame_split_across_reads() {
+    let mut codec = LeapCodec::new();
+    let mut buf = BytesMut::from(&br#"{"Header":{"Url":"/zone/1"#[..]);
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+
+    buf.extend_from_slice(br#"/status"}}"#);
+    buf.extend_from_slice(b"\r");
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+
+    buf.extend_from_slice(b"\n");
+    let msg = codec.decode(&mut buf).unwrap().unwrap();
+    assert_eq!(msg, json!({"Header": {"Url": "/zone/1/status"}}));
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn decodes_coalesced_frames() {
+    let mut codec = LeapCodec::new();
+    let mut buf = BytesMut::from(&b"{\"A\":1}\r\n{\"B\":2}\r\n{\"C\":"[..]);
+
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"A": 1})));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"B": 2})));
+    assert_eq!(codec.decode(&mut buf).unwrap(), None);
+
+    buf.extend_from_slice(b"3}\r\n");
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"C": 3})));
+}
+
+#[test]
+fn skips_empty_frames() {
+    let mut codec = LeapCodec::new();
+    let mut buf = BytesMut::from(&b"\r\n{\"A\":1}\r\n"[..]);
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({"A": 1})));
+}
+
+#[test]
+fn rejects_oversized_frames() {
+    let mut codec = LeapCodec::with_max_frame_size(8);
+    let mut buf = BytesMut::from(&b"{\"Oversized\":true"[..]);
+    assert!(matches!(codec.decode(&mut buf), Err(Error::Framing(_))));
+
+    let mut codec = LeapCodec::with_max_frame_size(8);
+    let mut buf = BytesMut::from(&b"{\"Oversized\":true}\r\n{}\r\n"[..]);
+    assert!(matches!(codec.decode(&mut buf), Err(Error::Framing(_))));
+    assert_eq!(codec.decode(&mut buf).unwrap(), Some(json!({})));
+}
+
+#[test]
+fn reports_truncated_frame_at_eof() {
+    let mut codec = LeapCodec::new();
+    let mut buf = BytesMut::from(&b"{\"A\":1}\r\n{\"B\""[..]);
+    assert_eq!(codec.decode_eof(&mut buf).unwrap(), Some(json!({"A": 1})));
+    assert!(matches!(codec.decode_eof(&mut buf), Err(Error::Framing(_))));
+}
+
+#[test]
+fn encodes_with_crlf_delimiter() {
+    let mut codec = LeapCodec::new();
+    let mut buf = BytesMut::new();
+    codec.encode(json!({"A": 1}), &mut buf).unwrap();
+    assert_eq!(&buf[..], b"{\"A\":1}\r\n");
+}
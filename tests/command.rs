@@ -0,0 +1,103 @@
+use casita::{
+    leap::{
+        command::{
+            format_duration, parse_duration, GoToDimmedLevel, GoToFanSpeed, GoToLevel,
+            GoToSpectrumTuningLevel, GoToSwitchedLevel, Raise, ZoneCommand,
+        },
+        CommuniqueType, FanSpeed, SwitchedLevel,
+    },
+    Error,
+};
+use serde_json::json;
+use std::time::Duration;
+
+#[test]
+fn builds_go_to_level_request() {
+    let msg = GoToLevel::new(75).message(3).unwrap();
+    assert_eq!(msg.communique_type, CommuniqueType::CreateRequest);
+    assert_eq!(msg.header.url, "/zone/3/commandprocessor");
+    assert_eq!(
+        serde_json::to_value(&msg).unwrap()["Body"],
+        json!({
+            "Command": {
+                "CommandType": "GoToLevel",
+                "Parameter": [{ "Type": "Level", "Value": 75 }],
+            }
+        })
+    );
+}
+
+#[test]
+fn builds_dimmed_level_with_times() {
+    let command = GoToDimmedLevel::new(40)
+        .fade_time(Duration::from_secs(2))
+        .delay_time(Duration::from_secs(90))
+        .command()
+        .unwrap();
+    assert_eq!(
+        command,
+        json!({
+            "CommandType": "GoToDimmedLevel",
+            "DimmedLevelParameters": {
+                "Level": 40,
+                "FadeTime": "00:00:02",
+                "DelayTime": "00:01:30",
+            },
+        })
+    );
+}
+
+#[test]
+fn builds_switched_fan_and_raise_commands() {
+    assert_eq!(
+        GoToSwitchedLevel::new(SwitchedLevel::Off)
+            .command()
+            .unwrap(),
+        json!({
+            "CommandType": "GoToSwitchedLevel",
+            "SwitchedLevelParameters": { "SwitchedLevel": "Off" },
+        })
+    );
+    assert_eq!(
+        GoToFanSpeed::new(FanSpeed::Medium).command().unwrap()["FanSpeedParameters"]["FanSpeed"],
+        "Medium"
+    );
+    assert_eq!(Raise.command().unwrap(), json!({ "CommandType": "Raise" }));
+}
+
+#[test]
+fn rejects_out_of_range_values() {
+    assert!(matches!(
+        GoToLevel::new(101).message(1),
+        Err(Error::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        GoToSpectrumTuningLevel::new(400, 50).command(),
+        Err(Error::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        GoToDimmedLevel::new(50)
+            .fade_time(Duration::from_millis(1500))
+            .command(),
+        Err(Error::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        GoToFanSpeed::new(FanSpeed::Unknown("Turbo".to_owned())).command(),
+        Err(Error::InvalidCommand(_))
+    ));
+}
+
+#[test]
+fn formats_and_parses_durations() {
+    assert_eq!(
+        format_duration(Duration::from_secs(3723)).unwrap(),
+        "01:02:03"
+    );
+    assert_eq!(
+        parse_duration("01:02:03").unwrap(),
+        Duration::from_secs(3723)
+    );
+    assert!(parse_duration("1:02:03").is_err());
+    assert!(parse_duration("00:60:00").is_err());
+    assert!(format_duration(Duration::from_secs(100 * 3600)).is_err());
+}
@@ -0,0 +1,63 @@
+//! Helpers shared by the integration tests. Each test crate uses a different
+//! subset, so unused helpers are expected.
+#![allow(dead_code)]
+
+use casita::{testing::MockBridge, Client, ClientBuilder};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::Duration;
+
+/// Loads `tests/fixtures/<name>.json`.
+pub fn fixture(name: &str) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+        .with_extension("json");
+    let json = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+    serde_json::from_str(&json).unwrap()
+}
+
+/// Wraps `body` in a successful `ReadResponse` of the given body type.
+pub fn definition(body_type: &str, body: Value) -> Value {
+    json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": { "StatusCode": "200 OK", "MessageBodyType": body_type },
+        "Body": body,
+    })
+}
+
+pub fn builder(bridge: &MockBridge) -> ClientBuilder {
+    Client::builder(bridge.client_certs().unwrap(), bridge.host())
+}
+
+pub async fn connect(bridge: &MockBridge) -> Client {
+    let client = builder(bridge).build().unwrap();
+    client.connect().await.unwrap();
+    client
+}
+
+/// An `UpdateResponse` setting `/zone/1` to `level`.
+pub fn zone_update(level: u64) -> Value {
+    json!({
+        "CommuniqueType": "UpdateResponse",
+        "Header": {
+            "Url": "/zone/1/status",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OneZoneStatus",
+        },
+        "Body": { "ZoneStatus": { "href": "/zone/1/status", "Level": level } },
+    })
+}
+
+/// Polls `check` until it holds, failing the test after five seconds.
+pub async fn eventually(mut check: impl FnMut() -> bool) {
+    let wait = async {
+        while !check() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), wait)
+        .await
+        .unwrap();
+}
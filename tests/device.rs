@@ -0,0 +1,109 @@
+mod common;
+
+use casita::{
+    leap::{Body, DeviceType, Href, Message},
+    testing::MockBridge,
+};
+use common::{connect, fixture};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[test]
+fn parses_device_inventory() {
+    let msg: Message = serde_json::from_value(fixture("device_multiple")).unwrap();
+    let devices = match msg.body {
+        Some(Body::MultipleDeviceDefinition(devices)) => devices,
+        other => panic!("expected devices, got {:?}", other),
+    };
+
+    assert!(devices[0].device_type.is_bridge());
+    assert_eq!(devices[0].serial_number, Some(12345678));
+
+    let pico = &devices[1];
+    assert_eq!(pico.device_type, DeviceType::Pico3ButtonRaiseLower);
+    assert!(pico.device_type.is_pico());
+    assert_eq!(pico.fully_qualified_name, vec!["Kitchen", "Pico"]);
+    assert_eq!(pico.button_groups, vec![Href::button_group(2)]);
+
+    assert_eq!(devices[2].local_zones, vec![Href::zone(1)]);
+    assert_eq!(devices[2].associated_area, Some(Href::area(3)));
+    assert_eq!(
+        devices[3].device_type,
+        DeviceType::Unknown("SunnataSwitch".to_owned())
+    );
+}
+
+#[tokio::test]
+async fn pages_through_device_list() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond_with("/device", |request: &Value| {
+        let paging = &request["Header"]["Paging"];
+        let limit = paging["Limit"].as_u64().unwrap();
+        let offset = paging["Offset"].as_u64().unwrap();
+        let devices: Vec<Value> = (offset..(offset + limit).min(150))
+            .map(|id| {
+                json!({
+                    "href": format!("/device/{}", id + 1),
+                    "Name": format!("Device {}", id + 1),
+                    "DeviceType": "WallDimmer",
+                })
+            })
+            .collect();
+        json!({
+            "CommuniqueType": "ReadResponse",
+            "Header": {
+                "StatusCode": "200 OK",
+                "MessageBodyType": "MultipleDeviceDefinition",
+                "Paging": { "Limit": limit, "Offset": offset },
+            },
+            "Body": { "Devices": devices },
+        })
+    });
+
+    let client = connect(&bridge).await;
+
+    let devices = client.devices().await.unwrap();
+    assert_eq!(devices.len(), 150);
+    assert_eq!(devices[149].href, Href::device(150));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn stops_when_bridge_ignores_offset() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond_with("/device", |request: &Value| {
+        let devices: Vec<Value> = (0..100)
+            .map(|id| {
+                json!({
+                    "href": format!("/device/{}", id + 1),
+                    "Name": format!("Device {}", id + 1),
+                    "DeviceType": "WallDimmer",
+                })
+            })
+            .collect();
+        json!({
+            "CommuniqueType": "ReadResponse",
+            "Header": {
+                "StatusCode": "200 OK",
+                "MessageBodyType": "MultipleDeviceDefinition",
+                "Paging": request["Header"]["Paging"].clone(),
+            },
+            "Body": { "Devices": devices },
+        })
+    });
+
+    let client = connect(&bridge).await;
+
+    let devices = tokio::time::timeout(Duration::from_secs(5), client.devices())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(devices.len(), 100);
+    let reads = bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["Header"]["Url"] == "/device")
+        .count();
+    assert_eq!(reads, 2);
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use casita::{
+    leap::{Body, ButtonEventType, Href, Message},
+    testing::MockBridge,
+    Gesture, GestureConfig, GestureDetector,
+};
+use common::{connect, definition};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn detector() -> GestureDetector {
+    GestureDetector::new(GestureConfig {
+        long_press: ms(500),
+        double_tap_window: ms(300),
+        hold_repeat_interval: Some(ms(200)),
+    })
+}
+
+#[test]
+fn emits_tap_after_double_tap_window() {
+    let mut detector = detector();
+    let start = Instant::now();
+
+    assert!(detector.on_event(&ButtonEventType::Press, start).is_empty());
+    assert!(detector
+        .on_event(&ButtonEventType::Release, start + ms(100))
+        .is_empty());
+    assert_eq!(detector.deadline(), Some(start + ms(400)));
+    assert!(detector.on_timeout(start + ms(399)).is_empty());
+    assert_eq!(detector.on_timeout(start + ms(400)), vec![Gesture::Tap]);
+    assert_eq!(detector.deadline(), None);
+}
+
+#[test]
+fn emits_double_tap() {
+    let mut detector = detector();
+    let start = Instant::now();
+
+    detector.on_event(&ButtonEventType::Press, start);
+    detector.on_event(&ButtonEventType::Release, start + ms(80));
+    detector.on_event(&ButtonEventType::Press, start + ms(200));
+    assert_eq!(
+        detector.on_event(&ButtonEventType::Release, start + ms(280)),
+        vec![Gesture::DoubleTap]
+    );
+    assert_eq!(detector.deadline(), None);
+}
+
+#[test]
+fn emits_long_press_and_hold_repeats() {
+    let mut detector = detector();
+    let start = Instant::now();
+
+    detector.on_event(&ButtonEventType::Press, start);
+    assert_eq!(
+        detector.on_timeout(start + ms(500)),
+        vec![Gesture::LongPress]
+    );
+    assert_eq!(
+        detector.on_timeout(start + ms(900)),
+        vec![
+            Gesture::HoldRepeat { count: 1 },
+            Gesture::HoldRepeat { count: 2 },
+        ]
+    );
+    assert!(detector
+        .on_event(&ButtonEventType::Release, start + ms(950))
+        .is_empty());
+    assert_eq!(detector.deadline(), None);
+}
+
+#[test]
+fn long_press_after_tap_reports_both() {
+    let mut detector = detector();
+    let start = Instant::now();
+
+    detector.on_event(&ButtonEventType::Press, start);
+    detector.on_event(&ButtonEventType::Release, start + ms(50));
+    detector.on_event(&ButtonEventType::Press, start + ms(150));
+    assert_eq!(
+        detector.on_timeout(start + ms(650)),
+        vec![Gesture::Tap, Gesture::LongPress]
+    );
+}
+
+#[test]
+fn late_event_flushes_pending_tap() {
+    let mut detector = detector();
+    let start = Instant::now();
+
+    detector.on_event(&ButtonEventType::Press, start);
+    detector.on_event(&ButtonEventType::Release, start + ms(50));
+    assert_eq!(
+        detector.on_event(&ButtonEventType::Press, start + ms(1000)),
+        vec![Gesture::Tap]
+    );
+}
+
+fn button_event(event_type: &str) -> Value {
+    json!({
+        "CommuniqueType": "UpdateResponse",
+        "Header": {
+            "Url": "/button/101/status/event",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OneButtonStatusEvent",
+        },
+        "Body": {
+            "ButtonStatus": {
+                "Button": { "href": "/button/101" },
+                "ButtonEvent": { "EventType": event_type },
+            },
+        },
+    })
+}
+
+#[test]
+fn parses_button_status_event() {
+    let msg: Message = serde_json::from_value(button_event("Press")).unwrap();
+    match msg.body {
+        Some(Body::OneButtonStatusEvent(status)) => {
+            assert_eq!(status.button, Href::button(101));
+            assert_eq!(status.button_event.event_type, ButtonEventType::Press);
+        }
+        other => panic!("expected a button status, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn streams_gestures_from_pico() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond(
+        "/device",
+        definition(
+            "MultipleDeviceDefinition",
+            json!({ "Devices": [{
+                "href": "/device/5",
+                "Name": "Pico",
+                "DeviceType": "Pico3ButtonRaiseLower",
+                "ButtonGroups": [{ "href": "/buttongroup/2" }],
+            }] }),
+        ),
+    );
+    bridge.respond(
+        "/buttongroup",
+        definition(
+            "MultipleButtonGroupDefinition",
+            json!({ "ButtonGroups": [{
+                "href": "/buttongroup/2",
+                "Parent": { "href": "/device/5" },
+                "Buttons": [{ "href": "/button/101" }],
+            }] }),
+        ),
+    );
+    bridge.respond(
+        "/button",
+        definition(
+            "MultipleButtonDefinition",
+            json!({ "Buttons": [
+                {
+                    "href": "/button/101",
+                    "ButtonNumber": 2,
+                    "Parent": { "href": "/buttongroup/2" },
+                },
+                {
+                    "href": "/button/900",
+                    "ButtonNumber": 1,
+                    "Parent": { "href": "/buttongroup/99" },
+                },
+            ] }),
+        ),
+    );
+
+    let client = connect(&bridge).await;
+
+    let mut gestures = client
+        .button_gestures(GestureConfig {
+            double_tap_window: ms(50),
+            ..GestureConfig::default()
+        })
+        .await
+        .unwrap();
+    bridge
+        .wait_for_request("/button/101/status/event")
+        .await
+        .unwrap();
+    let reads: Vec<_> = bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["CommuniqueType"] == "ReadRequest")
+        .map(|msg| msg["Header"]["Url"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(reads, ["/device", "/buttongroup", "/button"]);
+    let subscribes = bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["CommuniqueType"] == "SubscribeRequest")
+        .count();
+    assert_eq!(subscribes, 1);
+
+    bridge.push(button_event("Press")).await.unwrap();
+    bridge.push(button_event("Release")).await.unwrap();
+    let gesture = tokio::time::timeout(Duration::from_secs(5), gestures.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(gesture.device, "Pico");
+    assert_eq!(gesture.device_href, Href::device(5));
+    assert_eq!(gesture.button, Href::button(101));
+    assert_eq!(gesture.button_number, 2);
+    assert_eq!(gesture.gesture, Gesture::Tap);
+    client.disconnect().await.unwrap();
+}
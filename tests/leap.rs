@@ -0,0 +1,189 @@
+use casita::leap::{Body, CommuniqueType, Href, Message, StatusCode};
+use serde_json::json;
+
+#[test]
+fn parses_exception_response() {
+    let msg: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ExceptionResponse",
+        "Header": { "Url": "/zone/99/status", "StatusCode": "404 NotFound" },
+    }))
+    .unwrap();
+    assert_eq!(msg.communique_type, CommuniqueType::ExceptionResponse);
+}
+
+#[test]
+fn keeps_unknown_communique_types() {
+    let msg: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ExecuteRequest",
+        "Header": { "Url": "/zone/1/commandprocessor" },
+    }))
+    .unwrap();
+    assert_eq!(
+        msg.communique_type,
+        CommuniqueType::Unknown("ExecuteRequest".to_owned())
+    );
+    assert!(msg.communique_type.is_request());
+
+    let value = serde_json::to_value(&msg).unwrap();
+    assert_eq!(value["CommuniqueType"], "ExecuteRequest");
+}
+
+#[test]
+fn pairs_requests_with_responses() {
+    assert_eq!(
+        CommuniqueType::CreateRequest.expected_response_type(),
+        Some(CommuniqueType::CreateResponse)
+    );
+    assert_eq!(
+        CommuniqueType::UnsubscribeRequest.expected_response_type(),
+        Some(CommuniqueType::UnsubscribeResponse)
+    );
+    assert_eq!(CommuniqueType::ReadResponse.expected_response_type(), None);
+    assert!(CommuniqueType::ExceptionResponse.is_response());
+    assert!(!CommuniqueType::ExceptionResponse.is_request());
+}
+
+#[test]
+fn parses_status_codes() {
+    let status = StatusCode::parse("404 NotFound");
+    assert_eq!(status, StatusCode::new(404, "NotFound"));
+    assert!(status.is_client_error());
+    assert!(!status.is_success());
+    assert_eq!(status.to_string(), "404 NotFound");
+
+    let msg: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": { "Url": "/server/1/status/ping", "StatusCode": "200 OK" },
+    }))
+    .unwrap();
+    assert!(msg.header.status_code.as_ref().unwrap().is_success());
+    assert!(msg.into_result().is_ok());
+}
+
+#[test]
+fn round_trips_full_header() {
+    let raw = json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": {
+            "Url": "/device",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "MultipleDeviceDefinition",
+            "ContentType": "application/json",
+            "Paging": { "Limit": 25, "Offset": 50, "TotalCount": 60 },
+            "Vendor": { "Firmware": "08.25" },
+        },
+    });
+    let msg: Message = serde_json::from_value(raw.clone()).unwrap();
+    assert_eq!(
+        msg.header.message_body_type.as_deref(),
+        Some("MultipleDeviceDefinition")
+    );
+    assert_eq!(msg.header.paging.as_ref().unwrap().offset, Some(50));
+    assert_eq!(msg.header.extra["Vendor"]["Firmware"], "08.25");
+
+    assert_eq!(serde_json::to_value(&msg).unwrap(), raw);
+}
+
+#[test]
+fn selects_body_by_message_body_type() {
+    let raw = json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": {
+            "Url": "/server/1/status/ping",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OnePingResponse",
+        },
+        "Body": { "PingResponse": { "LEAPVersion": 1.115 } },
+    });
+    let msg: Message = serde_json::from_value(raw.clone()).unwrap();
+    match &msg.body {
+        Some(Body::OnePingResponse(ping)) => assert_eq!(ping.leap_version, 1.115),
+        other => panic!("expected a ping response, got {:?}", other),
+    }
+    assert_eq!(serde_json::to_value(&msg).unwrap(), raw);
+}
+
+#[test]
+fn round_trips_unmodeled_body_fields() {
+    let raw = json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": {
+            "Url": "/device/1",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "OneDeviceDefinition",
+        },
+        "Body": {
+            "Device": {
+                "href": "/device/1",
+                "Name": "Smart Bridge",
+                "DeviceType": "SmartBridge",
+                "AddressedState": "Addressed",
+                "FirmwareImage": {
+                    "Firmware": { "DisplayName": "08.25.17f000" },
+                    "Installed": {
+                        "Year": 2021,
+                        "Month": 4,
+                        "Day": 12,
+                        "Hour": 8,
+                        "Minute": 30,
+                        "Second": 0,
+                    },
+                },
+            },
+        },
+    });
+    let msg: Message = serde_json::from_value(raw.clone()).unwrap();
+    match &msg.body {
+        Some(Body::OneDeviceDefinition(device)) => {
+            assert_eq!(device.name, "Smart Bridge");
+            assert_eq!(device.extra["AddressedState"], "Addressed");
+            assert!(!device.extra.contains_key("href"));
+        }
+        other => panic!("expected a device, got {:?}", other),
+    }
+    assert_eq!(serde_json::to_value(&msg).unwrap(), raw);
+}
+
+#[test]
+fn falls_back_to_raw_bodies() {
+    let msg: Message = serde_json::from_value(json!({
+        "CommuniqueType": "ReadResponse",
+        "Header": { "Url": "/system", "MessageBodyType": "OneSystemDefinition" },
+        "Body": { "System": { "TimeZone": "America/New_York" } },
+    }))
+    .unwrap();
+    match msg.body {
+        Some(Body::Raw(body)) => assert_eq!(body["System"]["TimeZone"], "America/New_York"),
+        other => panic!("expected a raw body, got {:?}", other),
+    }
+}
+
+#[test]
+fn parses_and_builds_hrefs() {
+    let href: Href = "/device/4/buttongroup".parse().unwrap();
+    assert_eq!(href.kind(), "device");
+    assert_eq!(href.id(), Some(4));
+    assert_eq!(href.segments()[1].name, "buttongroup");
+
+    let href: Href = "/area/3/associatedzone".parse().unwrap();
+    assert_eq!(href.root(), Href::area(3));
+
+    assert_eq!(Href::zone(12).status().to_string(), "/zone/12/status");
+    assert_eq!(
+        Href::zone(12).commandprocessor().to_string(),
+        "/zone/12/commandprocessor"
+    );
+    assert_eq!(Href::ping().to_string(), "/server/1/status/ping");
+    assert!("zone/12".parse::<Href>().is_err());
+    assert!("/zone/12/34".parse::<Href>().is_err());
+}
+
+#[test]
+fn serializes_href_as_object() {
+    let href: Href = serde_json::from_value(json!({ "href": "/zone/12" })).unwrap();
+    assert_eq!(href, Href::zone(12));
+    assert_eq!(
+        serde_json::to_value(&href).unwrap(),
+        json!({ "href": "/zone/12" })
+    );
+}
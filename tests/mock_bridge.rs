@@ -0,0 +1,214 @@
+mod common;
+
+use casita::{
+    leap::{self, Body, CommuniqueType},
+    testing::MockBridge,
+    Error, MessageFilter, OverflowPolicy,
+};
+use common::{builder, connect, zone_update};
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn answers_ping_over_tls() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let ping = leap::Message::new(
+        CommuniqueType::ReadRequest,
+        "/server/1/status/ping".to_owned(),
+    );
+    let pong = client.request(ping).await.unwrap();
+    assert_eq!(pong.communique_type, CommuniqueType::ReadResponse);
+    assert!(pong.header.status_code.unwrap().is_success());
+
+    let request = bridge
+        .wait_for_request("/server/1/status/ping")
+        .await
+        .unwrap();
+    assert_eq!(request["CommuniqueType"], "ReadRequest");
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn returns_scripted_responses() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond(
+        "/zone/1/status",
+        json!({
+            "CommuniqueType": "ReadResponse",
+            "Header": { "StatusCode": "200 OK", "MessageBodyType": "OneZoneStatus" },
+            "Body": { "ZoneStatus": { "href": "/zone/1/status", "Level": 75 } },
+        }),
+    );
+    let client = connect(&bridge).await;
+
+    let request = leap::Message::new(CommuniqueType::ReadRequest, "/zone/1/status".to_owned());
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.header.url, "/zone/1/status");
+    match response.body {
+        Some(Body::OneZoneStatus(status)) => assert_eq!(status.level, Some(75)),
+        other => panic!("expected a zone status, got {:?}", other),
+    }
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn delivers_pushed_updates_to_subscriptions() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let mut subscription = client.subscribe("/zone/1/status").await.unwrap();
+    let request = bridge.wait_for_request("/zone/1/status").await.unwrap();
+    assert_eq!(request["CommuniqueType"], "SubscribeRequest");
+
+    bridge
+        .push(json!({
+            "CommuniqueType": "UpdateResponse",
+            "Header": {
+                "Url": "/zone/1/status",
+                "StatusCode": "200 OK",
+                "MessageBodyType": "OneZoneStatus",
+            },
+            "Body": { "ZoneStatus": { "href": "/zone/1/status", "Level": 20 } },
+        }))
+        .await
+        .unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.communique_type, CommuniqueType::UpdateResponse);
+    match update.body {
+        Some(Body::OneZoneStatus(status)) => assert_eq!(status.level, Some(20)),
+        other => panic!("expected a zone status, got {:?}", other),
+    }
+    client.disconnect().await.unwrap();
+}
+
+fn ping() -> leap::Message {
+    leap::Message::new(
+        CommuniqueType::ReadRequest,
+        "/server/1/status/ping".to_owned(),
+    )
+}
+
+#[tokio::test]
+async fn slow_listener_lags_while_requests_complete() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = builder(&bridge).read_capacity(1).build().unwrap();
+    let mut updates = client.listen(MessageFilter::new().url_prefix("/zone"));
+    client.connect().await.unwrap();
+    bridge.wait_for_connection().await.unwrap();
+
+    for level in 1..=3 {
+        bridge.push(zone_update(level)).await.unwrap();
+    }
+    let pong = tokio::time::timeout(Duration::from_secs(5), client.request(ping()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(pong.header.status_code.unwrap().is_success());
+
+    match updates.recv().await {
+        Err(Error::Lagged(skipped)) => assert_eq!(skipped, 2),
+        other => panic!("expected the listener to lag, got {:?}", other),
+    }
+    let update = updates.recv().await.unwrap();
+    assert_eq!(update["Body"]["ZoneStatus"]["Level"], 3);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn blocked_listener_does_not_stall_requests() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = builder(&bridge)
+        .read_capacity(1)
+        .overflow_policy(OverflowPolicy::Block)
+        .build()
+        .unwrap();
+    let mut updates = client.listen(MessageFilter::new().url_prefix("/zone"));
+    client.connect().await.unwrap();
+    bridge.wait_for_connection().await.unwrap();
+
+    for level in 1..=10 {
+        bridge.push(zone_update(level)).await.unwrap();
+    }
+    let pong = tokio::time::timeout(Duration::from_secs(5), client.request(ping()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(pong.header.status_code.unwrap().is_success());
+
+    let mut levels = Vec::new();
+    let mut skipped = 0;
+    while levels.last() != Some(&10) {
+        match tokio::time::timeout(Duration::from_secs(5), updates.recv())
+            .await
+            .unwrap()
+        {
+            Ok(update) => levels.push(update["Body"]["ZoneStatus"]["Level"].as_u64().unwrap()),
+            Err(Error::Lagged(count)) => skipped += count,
+            Err(err) => panic!("unexpected error {:?}", err),
+        }
+    }
+    // One message waits in the listener, one in the fan-out task and one in
+    // its queue; everything else was dropped and reported as lag.
+    assert!(levels.len() <= 3, "{:?}", levels);
+    assert!(
+        levels.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        levels
+    );
+    assert_eq!(levels.len() as u64 + skipped, 10);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_certs_pings_mock_bridge() {
+    let bridge = MockBridge::start().await.unwrap();
+    let dir = std::env::temp_dir().join(format!("casita-test-certs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    bridge.write_client_certs(&dir).unwrap();
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_test_certs"))
+        .arg(bridge.host())
+        .current_dir(&dir)
+        .output()
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Response from Caseta Hub!"), "{}", stdout);
+    assert!(bridge
+        .received()
+        .iter()
+        .any(|msg| msg["Header"]["Url"] == "/server/1/status/ping"));
+}
+
+#[tokio::test]
+async fn surfaces_exception_responses_as_leap_errors() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let request = leap::Message::new(CommuniqueType::ReadRequest, "/zone/99/status".to_owned());
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.communique_type, CommuniqueType::ExceptionResponse);
+
+    match response.into_result() {
+        Err(Error::Leap(err)) => {
+            let status = err.status.unwrap();
+            assert_eq!(status.code, 404);
+            assert!(status.is_client_error());
+            assert_eq!(
+                err.message.as_deref(),
+                Some("The requested resource does not exist.")
+            );
+        }
+        other => panic!("expected a LEAP error, got {:?}", other),
+    }
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use casita::{
+    leap::{Body, Href, Message, OccupancyStatus},
+    testing::MockBridge,
+    OccupancyEvent,
+};
+use common::{connect, fixture};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+#[test]
+fn parses_occupancy_groups() {
+    let msg: Message = serde_json::from_value(fixture("occupancy_group_multiple")).unwrap();
+    let groups = match msg.body {
+        Some(Body::MultipleOccupancyGroupDefinition(groups)) => groups,
+        other => panic!("expected occupancy groups, got {:?}", other),
+    };
+
+    assert_eq!(groups[0].href, Href::occupancy_group(2));
+    assert_eq!(
+        groups[0].associated_sensors[0].occupancy_sensor,
+        "/occupancysensor/3".parse().unwrap()
+    );
+    assert_eq!(groups[0].area(), Some(&Href::area(4)));
+    assert!(groups[1].associated_sensors.is_empty());
+}
+
+#[test]
+fn parses_occupancy_statuses() {
+    let msg: Message = serde_json::from_value(fixture("occupancy_status_multiple")).unwrap();
+    let statuses = match msg.body {
+        Some(Body::MultipleOccupancyGroupStatus(statuses)) => statuses,
+        other => panic!("expected occupancy statuses, got {:?}", other),
+    };
+
+    assert_eq!(statuses[0].occupancy_group, Href::occupancy_group(2));
+    assert!(statuses[0].occupancy_status.is_occupied());
+    assert_eq!(
+        statuses[1].occupancy_status,
+        OccupancyStatus::Unknown("Unknown".to_owned())
+    );
+}
+
+fn status_update(group: u32, status: &str) -> Value {
+    json!({
+        "CommuniqueType": "UpdateResponse",
+        "Header": {
+            "Url": "/occupancygroup/status",
+            "StatusCode": "200 OK",
+            "MessageBodyType": "MultipleOccupancyGroupStatus",
+        },
+        "Body": {
+            "OccupancyGroupStatuses": [{
+                "href": format!("/occupancygroup/{}/status", group),
+                "OccupancyGroup": { "href": format!("/occupancygroup/{}", group) },
+                "OccupancyStatus": status,
+            }],
+        },
+    })
+}
+
+#[tokio::test]
+async fn streams_occupancy_changes_with_areas() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/occupancygroup", fixture("occupancy_group_multiple"));
+    bridge.respond(
+        "/occupancygroup/status",
+        fixture("occupancy_status_multiple"),
+    );
+    let client = connect(&bridge).await;
+
+    let mut events = client.occupancy_events().await.unwrap();
+    assert_eq!(
+        events.status(&Href::occupancy_group(2)),
+        Some(&OccupancyStatus::Occupied)
+    );
+
+    bridge.push(status_update(2, "Occupied")).await.unwrap();
+    bridge.push(status_update(6, "Occupied")).await.unwrap();
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        event,
+        OccupancyEvent {
+            group: Href::occupancy_group(6),
+            area: Some(Href::area(7)),
+            status: OccupancyStatus::Occupied,
+        }
+    );
+    client.disconnect().await.unwrap();
+}
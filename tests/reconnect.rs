@@ -0,0 +1,123 @@
+mod common;
+
+use casita::{testing::MockBridge, Error, ReconnectPolicy};
+use common::{builder, eventually, zone_update};
+use futures::StreamExt;
+use std::time::Duration;
+
+fn policy() -> ReconnectPolicy {
+    ReconnectPolicy {
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(50),
+        multiplier: 2.0,
+        jitter: 0.0,
+    }
+}
+
+fn subscribe_requests(bridge: &MockBridge, url: &str) -> usize {
+    bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["CommuniqueType"] == "SubscribeRequest" && msg["Header"]["Url"] == url)
+        .count()
+}
+
+#[test]
+fn backs_off_exponentially_up_to_max_delay() {
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(10),
+        multiplier: 2.0,
+        jitter: 0.0,
+    };
+    let delays: Vec<_> = (0..6).map(|attempt| policy.delay(attempt)).collect();
+    assert_eq!(
+        delays,
+        [1, 2, 4, 8, 10, 10].map(Duration::from_secs).to_vec()
+    );
+    assert_eq!(policy.delay(u32::MAX), Duration::from_secs(10));
+}
+
+#[test]
+fn keeps_jitter_within_bounds() {
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_secs(4),
+        max_delay: Duration::from_secs(4),
+        multiplier: 2.0,
+        jitter: 0.25,
+    };
+    for attempt in 0..200 {
+        let delay = policy.delay(attempt % 4);
+        assert!(delay >= Duration::from_secs(3), "{:?}", delay);
+        assert!(delay <= policy.max_delay, "{:?}", delay);
+    }
+
+    let defaults = ReconnectPolicy::default();
+    for _ in 0..200 {
+        let delay = defaults.delay(10);
+        assert!(delay <= defaults.max_delay, "{:?}", delay);
+    }
+}
+
+#[tokio::test]
+async fn reconnects_and_replays_subscriptions() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = builder(&bridge).reconnect(policy()).build().unwrap();
+    client.connect().await.unwrap();
+    let mut subscription = client.subscribe("/zone/1/status").await.unwrap();
+    assert_eq!(subscribe_requests(&bridge, "/zone/1/status"), 1);
+
+    let mut status = client.watch_connection_status();
+    let first_connected = status.borrow().last_connected;
+    bridge.close_connections();
+    let reconnected = async {
+        loop {
+            {
+                let status = status.borrow();
+                if status.is_connected() && status.last_connected != first_connected {
+                    break;
+                }
+            }
+            status.changed().await.unwrap();
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), reconnected)
+        .await
+        .unwrap();
+    assert!(matches!(
+        client.connection_status().last_error,
+        Some(Error::NotConnected)
+    ));
+    eventually(|| subscribe_requests(&bridge, "/zone/1/status") == 2).await;
+    assert!(client.is_connected());
+
+    bridge.push(zone_update(40)).await.unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.header.url, "/zone/1/status");
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_replay_released_subscriptions() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = builder(&bridge).reconnect(policy()).build().unwrap();
+    client.connect().await.unwrap();
+    let subscription = client.subscribe("/zone/1/status").await.unwrap();
+    let _kept = client.subscribe("/zone/2/status").await.unwrap();
+    drop(subscription);
+    eventually(|| {
+        bridge
+            .received()
+            .iter()
+            .any(|msg| msg["CommuniqueType"] == "UnsubscribeRequest")
+    })
+    .await;
+
+    bridge.close_connections();
+    eventually(|| subscribe_requests(&bridge, "/zone/2/status") == 2).await;
+    assert_eq!(subscribe_requests(&bridge, "/zone/1/status"), 1);
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use casita::{
+    leap::{
+        command::{ButtonCommand, PressAndRelease},
+        CommuniqueType, Href,
+    },
+    testing::MockBridge,
+    Error,
+};
+use common::{connect, fixture};
+use serde_json::json;
+
+#[test]
+fn builds_press_and_release_request() {
+    let msg = PressAndRelease.message(&Href::virtual_button(3)).unwrap();
+    assert_eq!(msg.communique_type, CommuniqueType::CreateRequest);
+    assert_eq!(msg.header.url, "/virtualbutton/3/commandprocessor");
+    assert_eq!(
+        serde_json::to_value(&msg).unwrap()["Body"],
+        json!({ "Command": { "CommandType": "PressAndRelease" } })
+    );
+
+    assert!(matches!(
+        PressAndRelease.message(&Href::zone(3)),
+        Err(Error::InvalidCommand(_))
+    ));
+}
+
+#[tokio::test]
+async fn lists_only_programmed_scenes() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/virtualbutton", fixture("virtual_button_multiple"));
+    let client = connect(&bridge).await;
+
+    assert_eq!(client.virtual_buttons().await.unwrap().len(), 3);
+    let scenes = client.scenes().await.unwrap();
+    let names: Vec<&str> = scenes.iter().map(|scene| scene.name.as_str()).collect();
+    assert_eq!(names, vec!["Arriving Home", "Movie Night"]);
+    assert_eq!(scenes[1].button_number, 2);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn activates_scenes_by_name_and_href() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/virtualbutton", fixture("virtual_button_multiple"));
+    bridge.respond(
+        "/virtualbutton/3/commandprocessor",
+        json!({
+            "CommuniqueType": "CreateResponse",
+            "Header": { "StatusCode": "201 Created" },
+        }),
+    );
+    let client = connect(&bridge).await;
+
+    let scene = client.activate_scene_by_name("Movie Night").await.unwrap();
+    assert_eq!(scene.href, Href::virtual_button(3));
+    let request = bridge
+        .wait_for_request("/virtualbutton/3/commandprocessor")
+        .await
+        .unwrap();
+    assert_eq!(request["Body"]["Command"]["CommandType"], "PressAndRelease");
+
+    assert!(matches!(
+        client.activate_scene_by_name("Button 2").await,
+        Err(Error::SceneNotFound(_))
+    ));
+    match client.activate_scene(&Href::virtual_button(9)).await {
+        Err(Error::Leap(err)) => assert_eq!(err.status.unwrap().code, 404),
+        other => panic!("expected a LEAP error, got {:?}", other),
+    }
+    client.disconnect().await.unwrap();
+}
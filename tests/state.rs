@@ -0,0 +1,132 @@
+use casita::{
+    testing::MockBridge, Client, ClientBuilder, ConnectionState, ConnectionStatus, Error,
+    ReconnectPolicy,
+};
+use std::time::Duration;
+use tokio::io::DuplexStream;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+async fn wait_for(
+    status: &mut watch::Receiver<ConnectionStatus>,
+    check: impl Fn(&ConnectionStatus) -> bool,
+) -> ConnectionStatus {
+    let wait = async {
+        loop {
+            {
+                let current = status.borrow();
+                if check(&current) {
+                    return current.clone();
+                }
+            }
+            status.changed().await.unwrap();
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(5), wait)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reports_lifecycle_transitions() {
+    let (transports_tx, transports_rx) = async_channel::unbounded::<Result<DuplexStream, Error>>();
+    let client = ClientBuilder::with_connector(move || {
+        let transports_rx = transports_rx.clone();
+        async move {
+            transports_rx
+                .recv()
+                .await
+                .map_err(|_| Error::NotConnected)?
+        }
+    })
+    .reconnect(ReconnectPolicy {
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(10),
+        multiplier: 1.0,
+        jitter: 0.0,
+    })
+    .build()
+    .unwrap();
+
+    let mut status = client.watch_connection_status();
+    let initial = client.connection_status();
+    assert!(matches!(
+        initial.state,
+        ConnectionState::Disconnected { .. }
+    ));
+    assert!(initial.last_connected.is_none());
+    assert!(initial.last_error.is_none());
+
+    let connecting = tokio::spawn({
+        let client = client.clone();
+        async move { client.connect().await }
+    });
+    wait_for(&mut status, |s| s.state == ConnectionState::Connecting).await;
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    transports_tx.send(Ok(client_io)).await.unwrap();
+    connecting.await.unwrap().unwrap();
+    let connected = wait_for(&mut status, ConnectionStatus::is_connected).await;
+    assert!(connected.last_connected.is_some());
+
+    drop(server_io);
+    let reconnecting = wait_for(&mut status, |s| {
+        s.state == ConnectionState::Reconnecting { attempt: 1 }
+    })
+    .await;
+    assert!(matches!(reconnecting.last_error, Some(Error::NotConnected)));
+
+    transports_tx.send(Err(Error::Timeout)).await.unwrap();
+    let retrying = wait_for(&mut status, |s| {
+        s.state == ConnectionState::Reconnecting { attempt: 2 }
+    })
+    .await;
+    assert!(matches!(retrying.last_error, Some(Error::Timeout)));
+
+    let (client_io, _server_io) = tokio::io::duplex(4096);
+    transports_tx.send(Ok(client_io)).await.unwrap();
+    let reconnected = wait_for(&mut status, ConnectionStatus::is_connected).await;
+    assert!(reconnected.last_connected > connected.last_connected);
+
+    client.disconnect().await.unwrap();
+    assert_eq!(
+        client.connection_status().state,
+        ConnectionState::Disconnected {
+            reason: "disconnected by client".to_owned()
+        }
+    );
+    assert!(!client.is_connected());
+}
+
+#[tokio::test]
+async fn reports_handshake_timeouts() {
+    let bridge = MockBridge::start().await.unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let silent = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        drop(stream);
+    });
+
+    let client = Client::builder(bridge.client_certs().unwrap(), addr.to_string())
+        .handshake_timeout(Duration::from_millis(200))
+        .build()
+        .unwrap();
+    let mut status = client.watch_connection_status();
+    let connecting = tokio::spawn({
+        let client = client.clone();
+        async move { client.connect().await }
+    });
+
+    wait_for(&mut status, |s| s.state == ConnectionState::Handshaking).await;
+    assert!(matches!(connecting.await.unwrap(), Err(Error::Timeout)));
+    let failed = client.connection_status();
+    assert_eq!(
+        failed.state,
+        ConnectionState::Disconnected {
+            reason: Error::Timeout.to_string()
+        }
+    );
+    assert!(matches!(failed.last_error, Some(Error::Timeout)));
+    silent.abort();
+}
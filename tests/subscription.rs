@@ -0,0 +1,152 @@
+mod common;
+
+use casita::{codec::LeapCodec, testing::MockBridge, ClientBuilder, Error, OnceConnector};
+use common::{connect, eventually, zone_update};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use std::time::Duration;
+use tokio_util::codec::Framed;
+
+fn requests(bridge: &MockBridge, communique_type: &str, url: &str) -> usize {
+    bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["CommuniqueType"] == communique_type && msg["Header"]["Url"] == url)
+        .count()
+}
+
+#[tokio::test]
+async fn unsubscribes_when_dropped() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let subscription = client.subscribe("/zone/1/status").await.unwrap();
+    assert_eq!(subscription.url(), "/zone/1/status");
+    drop(subscription);
+    eventually(|| requests(&bridge, "UnsubscribeRequest", "/zone/1/status") == 1).await;
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn resubscribes_after_drop() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let subscription = client.subscribe("/zone/1/status").await.unwrap();
+    drop(subscription);
+    let mut subscription = client.subscribe("/zone/1/status").await.unwrap();
+
+    let order: Vec<_> = bridge
+        .received()
+        .iter()
+        .filter(|msg| msg["Header"]["Url"] == "/zone/1/status")
+        .map(|msg| msg["CommuniqueType"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(
+        order,
+        ["SubscribeRequest", "UnsubscribeRequest", "SubscribeRequest"]
+    );
+
+    bridge.push(zone_update(20)).await.unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(5), subscription.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.header.url, "/zone/1/status");
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn shares_one_subscription_per_url() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let mut first = client.subscribe("/zone/1/status").await.unwrap();
+    let mut second = client.subscribe("/zone/1/status").await.unwrap();
+    assert_eq!(requests(&bridge, "SubscribeRequest", "/zone/1/status"), 1);
+
+    bridge.push(zone_update(30)).await.unwrap();
+    for subscription in [&mut first, &mut second] {
+        let update = tokio::time::timeout(Duration::from_secs(5), subscription.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.header.url, "/zone/1/status");
+    }
+
+    drop(first);
+    let _other = client.subscribe("/zone/2/status").await.unwrap();
+    assert_eq!(requests(&bridge, "UnsubscribeRequest", "/zone/1/status"), 0);
+    drop(second);
+    eventually(|| requests(&bridge, "UnsubscribeRequest", "/zone/1/status") == 1).await;
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn clones_register_their_own_listener() {
+    let bridge = MockBridge::start().await.unwrap();
+    let client = connect(&bridge).await;
+
+    let original = client.subscribe("/zone/1/status").await.unwrap();
+    let mut clone = original.clone();
+    assert_eq!(clone.url(), "/zone/1/status");
+    drop(original);
+
+    bridge.push(zone_update(60)).await.unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(5), clone.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(update.header.url, "/zone/1/status");
+    assert_eq!(requests(&bridge, "SubscribeRequest", "/zone/1/status"), 1);
+    assert_eq!(requests(&bridge, "UnsubscribeRequest", "/zone/1/status"), 0);
+
+    drop(clone);
+    eventually(|| requests(&bridge, "UnsubscribeRequest", "/zone/1/status") == 1).await;
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn later_subscribers_wait_for_in_flight_subscribe() {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client = ClientBuilder::with_connector(OnceConnector::new(client_io))
+        .build()
+        .unwrap();
+    client.connect().await.unwrap();
+    let mut server = Framed::new(server_io, LeapCodec::new());
+
+    let first = tokio::spawn({
+        let client = client.clone();
+        async move { client.subscribe("/zone/9/status").await }
+    });
+    let request = server.next().await.unwrap().unwrap();
+    assert_eq!(request["CommuniqueType"], "SubscribeRequest");
+
+    let mut second = tokio::spawn({
+        let client = client.clone();
+        async move { client.subscribe("/zone/9/status").await }
+    });
+    let waiting = tokio::time::timeout(Duration::from_millis(50), &mut second).await;
+    assert!(
+        waiting.is_err(),
+        "second subscriber returned before the response"
+    );
+
+    server
+        .send(json!({
+            "CommuniqueType": "ExceptionResponse",
+            "Header": {
+                "Url": "/zone/9/status",
+                "StatusCode": "404 NotFound",
+                "ClientTag": request["Header"]["ClientTag"].clone(),
+            },
+        }))
+        .await
+        .unwrap();
+
+    assert!(matches!(first.await.unwrap(), Err(Error::Leap(_))));
+    assert!(matches!(second.await.unwrap(), Err(Error::Leap(_))));
+    let unexpected = tokio::time::timeout(Duration::from_millis(50), server.next()).await;
+    assert!(unexpected.is_err(), "{:?}", unexpected);
+    client.disconnect().await.unwrap();
+}
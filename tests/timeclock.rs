@@ -0,0 +1,234 @@
+mod common;
+
+use casita::{
+    leap::{
+        upcoming_events, Body, CommuniqueType, Date, DaysOfWeek, Href, Location, Message,
+        ScheduleType, TimeOfDay, TimeclockEvent, TimeclockEventType,
+    },
+    testing::MockBridge,
+    Error,
+};
+use common::{connect, fixture};
+use serde_json::json;
+
+fn events() -> Vec<TimeclockEvent> {
+    let msg: Message = serde_json::from_value(fixture("timeclock_event_multiple")).unwrap();
+    match msg.body {
+        Some(Body::MultipleTimeclockEventDefinition(events)) => events,
+        other => panic!("expected timeclock events, got {:?}", other),
+    }
+}
+
+fn philadelphia() -> Location {
+    Location::new(39.9526, -75.1652, -4 * 60)
+}
+
+fn minutes_between(a: TimeOfDay, b: TimeOfDay) -> i64 {
+    (a.minutes() - b.minutes()).abs()
+}
+
+#[test]
+fn does_calendar_math() {
+    let date = Date::new(2024, 2, 28).unwrap();
+    assert_eq!(date.add_days(1), Date::new(2024, 2, 29).unwrap());
+    assert_eq!(date.add_days(2), Date::new(2024, 3, 1).unwrap());
+    assert_eq!(Date::from_days(0), Date::new(1970, 1, 1).unwrap());
+    assert_eq!(Date::new(2024, 6, 21).unwrap().weekday(), 5);
+    assert!(matches!(
+        Date::new(2023, 2, 29),
+        Err(Error::InvalidTimeclock(_))
+    ));
+}
+
+#[test]
+fn approximates_sunrise_and_sunset() {
+    let (sunrise, sunset) = philadelphia()
+        .sun_times(Date::new(2024, 6, 21).unwrap())
+        .unwrap();
+    assert!(
+        minutes_between(sunrise, TimeOfDay::new(5, 32)) <= 5,
+        "{}",
+        sunrise
+    );
+    assert!(
+        minutes_between(sunset, TimeOfDay::new(20, 32)) <= 5,
+        "{}",
+        sunset
+    );
+
+    let tromso = Location::new(69.6492, 18.9553, 2 * 60);
+    assert_eq!(tromso.sun_times(Date::new(2024, 6, 21).unwrap()), None);
+}
+
+#[test]
+fn parses_timeclock_events() {
+    let events = events();
+    assert_eq!(events[0].event_type, TimeclockEventType::FixedTime);
+    assert_eq!(events[0].time_of_day, Some(TimeOfDay::new(6, 30)));
+    assert_eq!(events[0].preset, Some("/preset/21".parse().unwrap()));
+    assert_eq!(events[1].offset_minutes, Some(-15));
+    assert_eq!(events[1].virtual_button, Some(Href::virtual_button(3)));
+    assert_eq!(events[2].schedule_type, ScheduleType::SpecificDates);
+    assert!(!events[3].is_enabled());
+}
+
+#[test]
+fn lists_upcoming_events() {
+    let friday = Date::new(2024, 6, 21).unwrap();
+    let upcoming = upcoming_events(
+        &events(),
+        friday,
+        TimeOfDay::new(7, 0),
+        3,
+        Some(&philadelphia()),
+    );
+    let summary: Vec<(Date, &str)> = upcoming
+        .iter()
+        .map(|event| (event.date, event.name.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (friday, "Porch at Sunset"),
+            (friday.add_days(1), "Holiday Lights"),
+            (friday.add_days(1), "Porch at Sunset"),
+            (friday.add_days(2), "Porch at Sunset"),
+        ]
+    );
+    assert!(minutes_between(upcoming[0].time, TimeOfDay::new(20, 17)) <= 5);
+
+    let fixed_only = upcoming_events(&events(), friday, TimeOfDay::new(0, 0), 4, None);
+    let names: Vec<&str> = fixed_only.iter().map(|event| event.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["Weekday Wake", "Holiday Lights", "Weekday Wake"]
+    );
+}
+
+fn sun_event(name: &str, event_type: &str, offset_minutes: i32) -> TimeclockEvent {
+    serde_json::from_value(json!({
+        "href": "/timeclockevent/9",
+        "Name": name,
+        "EventType": event_type,
+        "OffsetMinutes": offset_minutes,
+        "ScheduleType": "DayOfWeek",
+        "DaysOfWeek": DaysOfWeek::every_day(),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn moves_offset_sun_events_across_midnight() {
+    let friday = Date::new(2024, 6, 21).unwrap();
+    let late = sun_event("Late Porch", "Sunset", 4 * 60);
+    let early = sun_event("Early Coffee", "Sunrise", -6 * 60);
+
+    let (date, time) = late.time_on(friday, Some(&philadelphia())).unwrap();
+    assert_eq!(date, friday.add_days(1));
+    assert!(
+        minutes_between(time, TimeOfDay::new(0, 32)) <= 5,
+        "{}",
+        time
+    );
+    let (date, time) = early.time_on(friday, Some(&philadelphia())).unwrap();
+    assert_eq!(date, friday.add_days(-1));
+    assert!(
+        minutes_between(time, TimeOfDay::new(23, 32)) <= 5,
+        "{}",
+        time
+    );
+
+    let upcoming = upcoming_events(
+        &[late, early],
+        friday,
+        TimeOfDay::new(7, 0),
+        2,
+        Some(&philadelphia()),
+    );
+    let summary: Vec<(Date, &str)> = upcoming
+        .iter()
+        .map(|event| (event.date, event.name.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (friday, "Early Coffee"),
+            (friday.add_days(1), "Late Porch"),
+            (friday.add_days(1), "Early Coffee"),
+        ]
+    );
+}
+
+#[test]
+fn builds_timeclock_event_requests() {
+    let mut event = events().remove(0);
+    let timeclock: Href = "/timeclock/1".parse().unwrap();
+    assert!(matches!(
+        event.create_request(&timeclock),
+        Err(Error::InvalidTimeclock(_))
+    ));
+
+    let new_event = TimeclockEvent {
+        href: None,
+        ..event.clone()
+    };
+    assert!(matches!(
+        new_event.update_request(),
+        Err(Error::InvalidTimeclock(_))
+    ));
+    let create = new_event.create_request(&timeclock).unwrap();
+    assert_eq!(create.communique_type, CommuniqueType::CreateRequest);
+    assert_eq!(create.header.url, "/timeclock/1/timeclockevent");
+    let body = &serde_json::to_value(&create).unwrap()["Body"]["TimeclockEvent"];
+    assert!(body.get("href").is_none());
+    assert_eq!(body["Parent"]["href"], "/timeclock/1");
+    assert_eq!(body["TimeOfDay"]["Hour"], 6);
+    let created: TimeclockEvent = serde_json::from_value(body.clone()).unwrap();
+    assert_eq!(created.href, None);
+    assert_eq!(created.parent, Some(timeclock));
+
+    let update = event.update_request().unwrap();
+    assert_eq!(update.communique_type, CommuniqueType::UpdateRequest);
+    assert_eq!(update.header.url, "/timeclockevent/1");
+    assert!(matches!(
+        update.body,
+        Some(Body::OneTimeclockEventDefinition(_))
+    ));
+
+    let delete = event.delete_request().unwrap();
+    assert_eq!(delete.communique_type, CommuniqueType::DeleteRequest);
+    assert!(delete.body.is_none());
+
+    event.days_of_week = Some(DaysOfWeek::default());
+    assert!(matches!(
+        event.update_request(),
+        Err(Error::InvalidTimeclock(_))
+    ));
+    event.days_of_week = None;
+    assert!(matches!(
+        event.update_request(),
+        Err(Error::InvalidTimeclock(_))
+    ));
+    event.days_of_week = Some(DaysOfWeek::every_day());
+    event.time_of_day = Some(TimeOfDay::new(24, 0));
+    assert!(matches!(
+        event.update_request(),
+        Err(Error::InvalidTimeclock(_))
+    ));
+}
+
+#[tokio::test]
+async fn reads_timeclock_events_from_bridge() {
+    let bridge = MockBridge::start().await.unwrap();
+    bridge.respond("/timeclockevent", fixture("timeclock_event_multiple"));
+    let client = connect(&bridge).await;
+
+    assert_eq!(client.timeclock_events().await.unwrap().len(), 4);
+    let upcoming = client
+        .upcoming_timeclock_events(7, &philadelphia())
+        .await
+        .unwrap();
+    assert!(upcoming.iter().any(|event| event.name == "Porch at Sunset"));
+    assert!(upcoming.iter().all(|event| event.name != "Vacation Mode"));
+    client.disconnect().await.unwrap();
+}
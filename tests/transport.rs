@@ -0,0 +1,151 @@
+use casita::{
+    codec::LeapCodec,
+    leap::{self, CommuniqueType},
+    Client, ClientBuilder, Error, MessageFilter, OnceConnector,
+};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use std::time::Duration;
+use tokio_util::codec::Framed;
+
+#[tokio::test]
+async fn runs_over_duplex_transport() {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client: Client = ClientBuilder::with_connector(OnceConnector::new(client_io))
+        .build()
+        .unwrap();
+    let mut updates = client.listen(MessageFilter::new().url_prefix("/zone"));
+    client.connect().await.unwrap();
+
+    let server = tokio::spawn(async move {
+        let mut server = Framed::new(server_io, LeapCodec::new());
+        let request = server.next().await.unwrap().unwrap();
+        assert_eq!(request["Header"]["Url"], "/server/1/status/ping");
+        server
+            .send(json!({
+                "CommuniqueType": "ReadResponse",
+                "Header": {
+                    "Url": "/server/1/status/ping",
+                    "StatusCode": "200 OK",
+                    "ClientTag": request["Header"]["ClientTag"].clone(),
+                }
+            }))
+            .await
+            .unwrap();
+        server
+            .send(json!({
+                "CommuniqueType": "UpdateResponse",
+                "Header": { "Url": "/zone/1/status" }
+            }))
+            .await
+            .unwrap();
+        server
+    });
+
+    let ping = leap::Message::new(
+        CommuniqueType::ReadRequest,
+        "/server/1/status/ping".to_owned(),
+    );
+    let pong = client.request(ping).await.unwrap();
+    assert!(pong.header.status_code.unwrap().is_success());
+
+    let update = updates.recv().await.unwrap();
+    assert_eq!(update["Header"]["Url"], "/zone/1/status");
+
+    let _server = server.await.unwrap();
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn times_out_unanswered_requests() {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client = ClientBuilder::with_connector(OnceConnector::new(client_io))
+        .request_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    client.connect().await.unwrap();
+    let mut server = Framed::new(server_io, LeapCodec::new());
+
+    let ping = leap::Message::new(
+        CommuniqueType::ReadRequest,
+        "/server/1/status/ping".to_owned(),
+    );
+    match client.request(ping.clone()).await {
+        Err(Error::Timeout) => {}
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+    let unanswered = server.next().await.unwrap().unwrap();
+
+    let answer = async {
+        let request = server.next().await.unwrap().unwrap();
+        assert_ne!(
+            request["Header"]["ClientTag"],
+            unanswered["Header"]["ClientTag"]
+        );
+        server
+            .send(json!({
+                "CommuniqueType": "ReadResponse",
+                "Header": {
+                    "Url": "/server/1/status/ping",
+                    "StatusCode": "200 OK",
+                    "ClientTag": request["Header"]["ClientTag"].clone(),
+                }
+            }))
+            .await
+            .unwrap();
+    };
+    let (pong, ()) = tokio::join!(client.request(ping), answer);
+    assert!(pong.unwrap().header.status_code.unwrap().is_success());
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn disconnect_fails_pending_requests_and_ends_subscriptions() {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client = ClientBuilder::with_connector(OnceConnector::new(client_io))
+        .build()
+        .unwrap();
+    client.connect().await.unwrap();
+    let mut server = Framed::new(server_io, LeapCodec::new());
+
+    let subscribe = async {
+        let request = server.next().await.unwrap().unwrap();
+        assert_eq!(request["CommuniqueType"], "SubscribeRequest");
+        server
+            .send(json!({
+                "CommuniqueType": "SubscribeResponse",
+                "Header": {
+                    "Url": "/zone/1/status",
+                    "StatusCode": "200 OK",
+                    "ClientTag": request["Header"]["ClientTag"].clone(),
+                }
+            }))
+            .await
+            .unwrap();
+    };
+    let (subscription, ()) = tokio::join!(client.subscribe("/zone/1/status"), subscribe);
+    let mut subscription = subscription.unwrap();
+
+    let pending = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let ping = leap::Message::new(
+                CommuniqueType::ReadRequest,
+                "/server/1/status/ping".to_owned(),
+            );
+            client.request(ping).await
+        }
+    });
+    let request = server.next().await.unwrap().unwrap();
+    assert_eq!(request["Header"]["Url"], "/server/1/status/ping");
+
+    client.disconnect().await.unwrap();
+    assert!(matches!(pending.await.unwrap(), Err(Error::NotConnected)));
+    assert!(subscription.next().await.is_none());
+    assert!(server.next().await.is_none());
+    assert!(!client.is_connected());
+    assert!(matches!(
+        client.subscribe("/zone/1/status").await,
+        Err(Error::NotConnected)
+    ));
+}
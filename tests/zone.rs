@@ -0,0 +1,86 @@
+mod common;
+
+use casita::leap::{Body, ControlType, FanSpeed, Href, Message, SwitchedLevel, ZoneStatus};
+use common::fixture;
+
+fn message(name: &str) -> Message {
+    serde_json::from_value(fixture(name)).unwrap()
+}
+
+fn zone_status(msg: Message) -> ZoneStatus {
+    match msg.body {
+        Some(Body::OneZoneStatus(status)) => status,
+        other => panic!("expected a zone status, got {:?}", other),
+    }
+}
+
+#[test]
+fn parses_dimmer_status() {
+    let status = zone_status(message("zone_status_dimmer"));
+    assert_eq!(status.href, Href::zone(1).status());
+    assert_eq!(status.level, Some(42));
+    assert_eq!(status.zone, Some(Href::zone(1)));
+    assert_eq!(status.status_accuracy.as_deref(), Some("Good"));
+}
+
+#[test]
+fn parses_switch_status() {
+    let status = zone_status(message("zone_status_switch"));
+    assert_eq!(status.switched_level, Some(SwitchedLevel::On));
+    assert_eq!(status.level, Some(100));
+}
+
+#[test]
+fn parses_fan_status() {
+    let status = zone_status(message("zone_status_fan"));
+    assert_eq!(status.fan_speed, Some(FanSpeed::MediumHigh));
+    assert_eq!(status.level, None);
+}
+
+#[test]
+fn parses_tuning_and_tilt_statuses() {
+    let msg = message("zone_status_multiple");
+    let statuses = match msg.body {
+        Some(Body::MultipleZoneStatus(statuses)) => statuses,
+        other => panic!("expected zone statuses, got {:?}", other),
+    };
+    assert_eq!(statuses.len(), 2);
+
+    let tuning = statuses[0].color_tuning_status.as_ref().unwrap();
+    assert_eq!(tuning.white_tuning_level.as_ref().unwrap().kelvin, 2700);
+    assert_eq!(statuses[0].vibrancy, Some(30));
+    assert_eq!(statuses[1].tilt, Some(50));
+}
+
+#[test]
+fn parses_zone_definitions() {
+    let msg = message("zone_definition_multiple");
+    let zones = match msg.body {
+        Some(Body::MultipleZoneDefinition(zones)) => zones,
+        other => panic!("expected zone definitions, got {:?}", other),
+    };
+
+    assert_eq!(zones[0].name, "Kitchen Pendants");
+    assert_eq!(zones[0].control_type, ControlType::Dimmed);
+    assert!(zones[0].category.as_ref().unwrap().is_light);
+    assert_eq!(zones[0].associated_area, Some(Href::area(3)));
+
+    assert_eq!(zones[1].control_type, ControlType::FanSpeed);
+    assert_eq!(
+        zones[2].control_type,
+        ControlType::Unknown("PhaseSelectableDimmed".to_owned())
+    );
+}
+
+#[test]
+fn round_trips_zone_fixtures() {
+    for name in [
+        "zone_status_dimmer",
+        "zone_status_multiple",
+        "zone_definition_multiple",
+    ] {
+        let raw = fixture(name);
+        let msg: Message = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&msg).unwrap(), raw);
+    }
+}